@@ -0,0 +1,64 @@
+// Parser throughput over the fixtures used by the unit tests.
+//
+// Run with `cargo bench --bench parser`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dop2rs::payloader::root::RootNode;
+use dop2rs::Dop2Parser;
+use std::hint::black_box;
+
+#[path = "../src/fixtures.rs"]
+mod fixtures;
+use fixtures::TEST_PAYLOADS;
+
+fn fixtures() -> Vec<(&'static str, Vec<u8>)> {
+    [
+        ("oven_14_130", TEST_PAYLOADS.oven_14_130),
+        ("oven_2_1586", TEST_PAYLOADS.oven_2_1586),
+        ("oven_9_19", TEST_PAYLOADS.oven_9_19),
+        ("oven_2_114", TEST_PAYLOADS.oven_2_114),
+        ("oven_1_209", TEST_PAYLOADS.oven_1_209),
+        ("oven_1_391", TEST_PAYLOADS.oven_1_391),
+        ("oven_2_1585", TEST_PAYLOADS.oven_2_1585),
+        ("washer_1_154", TEST_PAYLOADS.washer_1_154),
+    ]
+    .into_iter()
+    .map(|(name, payload)| (name, hex::decode(payload).unwrap()))
+    .collect()
+}
+
+fn root_node_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("RootNode::parse");
+    for (name, bytes) in fixtures() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &bytes, |b, bytes| {
+            b.iter(|| {
+                let mut parser = Dop2Parser::new(black_box(bytes));
+                RootNode::parse(&mut parser).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+// Reads a whole frame as u16 words through the cursor, which isolates the cost
+// of the primitive reads from building the tree.
+fn primitive_reads(c: &mut Criterion) {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_391).unwrap();
+    let mut group = c.benchmark_group("take_u16");
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("oven_1_391", |b| {
+        b.iter(|| {
+            let mut parser = Dop2Parser::new(black_box(&bytes));
+            let mut sum = 0u32;
+            while let Ok(word) = parser.take_u16() {
+                sum += u32::from(word);
+            }
+            sum
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, root_node_parse, primitive_reads);
+criterion_main!(benches);
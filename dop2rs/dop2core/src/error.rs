@@ -0,0 +1,410 @@
+// Error type shared by the DOP2 parser and the typed parse tree conversions
+
+use crate::root::Dop2PayloadsKind;
+use core::fmt;
+use core::str::FromStr;
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+
+/// One step on the way from a frame down to the value that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dop2PathSegment {
+    Unit(u16),
+    Attribute(u16),
+    Field(u16),
+    Element(usize),
+}
+
+impl fmt::Display for Dop2PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dop2PathSegment::Unit(unit) => write!(f, "unit {}", unit),
+            Dop2PathSegment::Attribute(attribute) => write!(f, "attr {}", attribute),
+            Dop2PathSegment::Field(field) => write!(f, "field {}", field),
+            Dop2PathSegment::Element(index) => write!(f, "element {}", index),
+        }
+    }
+}
+
+/// The `Dop2Limits` bound that a frame went beyond
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dop2Limit {
+    Depth,
+    Elements,
+    StringLength,
+    FrameSize,
+}
+
+impl fmt::Display for Dop2Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dop2Limit::Depth => write!(f, "nesting depth"),
+            Dop2Limit::Elements => write!(f, "element count"),
+            Dop2Limit::StringLength => write!(f, "string length"),
+            Dop2Limit::FrameSize => write!(f, "frame size"),
+        }
+    }
+}
+
+/// Location of a value inside a frame, outermost segment first,
+/// e.g. `unit 2 / attr 1585 / field 7 / field 3`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dop2Path(pub Vec<Dop2PathSegment>);
+
+impl Dop2Path {
+    pub fn segments(&self) -> &[Dop2PathSegment] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The field and element segments in query notation, e.g. `4[2].5`
+    pub fn dotted(&self) -> String {
+        let mut text = String::new();
+        for segment in &self.0 {
+            match segment {
+                Dop2PathSegment::Field(field) => {
+                    if !text.is_empty() {
+                        text.push('.');
+                    }
+                    text.push_str(&field.to_string());
+                }
+                Dop2PathSegment::Element(index) => text.push_str(&format!("[{}]", index)),
+                Dop2PathSegment::Unit(_) | Dop2PathSegment::Attribute(_) => {}
+            }
+        }
+        text
+    }
+}
+
+/// Parses query notation: field indices separated by dots, each optionally
+/// followed by array indices, e.g. `7.21.2` or `4[2].5`
+impl FromStr for Dop2Path {
+    type Err = Dop2Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || Dop2Error::InvalidPath {
+            text: text.to_string(),
+            path: Dop2Path::default(),
+        };
+        let mut segments = Vec::new();
+        for part in text.split('.') {
+            let (field, mut rest) = part.split_once('[').map_or((part, ""), |(field, rest)| (field, rest));
+            segments.push(Dop2PathSegment::Field(field.trim().parse().map_err(|_| invalid())?));
+            while !rest.is_empty() {
+                let (index, after) = rest.split_once(']').ok_or_else(invalid)?;
+                segments.push(Dop2PathSegment::Element(index.trim().parse().map_err(|_| invalid())?));
+                rest = match after {
+                    "" => "",
+                    after => after.strip_prefix('[').ok_or_else(invalid)?,
+                };
+            }
+        }
+        Ok(Dop2Path(segments))
+    }
+}
+
+impl From<Dop2PathSegment> for Dop2Path {
+    fn from(segment: Dop2PathSegment) -> Self {
+        Dop2Path(vec![segment])
+    }
+}
+
+impl fmt::Display for Dop2Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " / ")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything that can go wrong while decoding a frame or converting a parse
+/// tree into a typed payload.
+///
+/// Parse errors carry the byte offset into the frame; conversion errors work on
+/// an already parsed tree and carry the Rust type that was being built instead.
+/// Both carry the path to the offending field, which is filled in from the
+/// inside out as the error travels up through nested structs and arrays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dop2Error {
+    /// The frame ended before a value was complete
+    UnexpectedEof {
+        offset: usize,
+        needed: usize,
+        available: usize,
+        path: Dop2Path,
+    },
+    /// A field carried a type tag that has no `Dop2PayloadsKind`
+    UnknownTag { offset: usize, tag: u8, path: Dop2Path },
+    /// A field carried a known type tag that the parser cannot decode
+    UnsupportedKind {
+        offset: usize,
+        kind: Dop2PayloadsKind,
+        path: Dop2Path,
+    },
+    /// The bytes were read but do not form a valid value of their kind
+    InvalidValue {
+        offset: usize,
+        kind: Dop2PayloadsKind,
+        detail: String,
+        path: Dop2Path,
+    },
+    /// A byte other than the 0x20 padding byte followed the root struct
+    TrailingGarbage { offset: usize, byte: u8, path: Dop2Path },
+    /// The length word at the start of the frame disagrees with the decoded content
+    LengthMismatch {
+        offset: usize,
+        declared: u16,
+        actual: usize,
+        path: Dop2Path,
+    },
+    /// The frame goes beyond one of the limits the parser was given
+    LimitExceeded {
+        offset: usize,
+        limit: Dop2Limit,
+        value: usize,
+        max: usize,
+        path: Dop2Path,
+    },
+    /// Query notation that does not describe a path, e.g. `7..2` or `4[x]`
+    InvalidPath { text: String, path: Dop2Path },
+    /// Nothing in the tree sits at the path
+    PathNotFound { path: Dop2Path },
+    /// A field exists but holds a different payload kind than the Rust type expects
+    TypeMismatch {
+        type_name: &'static str,
+        expected: Dop2PayloadsKind,
+        found: Dop2PayloadsKind,
+        path: Dop2Path,
+    },
+    /// A mandatory field is not present in the struct
+    MissingField {
+        type_name: &'static str,
+        expected: Dop2PayloadsKind,
+        path: Dop2Path,
+    },
+    /// The payload has the right kind but its value does not fit the Rust type
+    InvalidConversion {
+        type_name: &'static str,
+        detail: String,
+        path: Dop2Path,
+    },
+}
+
+impl Dop2Error {
+    pub fn unexpected_eof(offset: usize, needed: usize, available: usize) -> Self {
+        Dop2Error::UnexpectedEof {
+            offset,
+            needed,
+            available,
+            path: Dop2Path::default(),
+        }
+    }
+
+    pub fn type_mismatch(
+        type_name: &'static str,
+        expected: Dop2PayloadsKind,
+        found: Dop2PayloadsKind,
+    ) -> Self {
+        Dop2Error::TypeMismatch {
+            type_name,
+            expected,
+            found,
+            path: Dop2Path::default(),
+        }
+    }
+
+    pub fn missing_field(type_name: &'static str, expected: Dop2PayloadsKind) -> Self {
+        Dop2Error::MissingField {
+            type_name,
+            expected,
+            path: Dop2Path::default(),
+        }
+    }
+
+    pub fn invalid_conversion(type_name: &'static str, detail: impl Into<String>) -> Self {
+        Dop2Error::InvalidConversion {
+            type_name,
+            detail: detail.into(),
+            path: Dop2Path::default(),
+        }
+    }
+
+    /// Names the struct whose field failed to convert.
+    ///
+    /// A conversion that failed right at the field, e.g. `E16(9999)` that is no
+    /// `ProgramIdOven`, becomes `E16(9999) into ProgramIdOven while building
+    /// CSContext`. Errors from deeper down already name their own struct and
+    /// are returned as they are.
+    pub fn in_struct(self, struct_name: &'static str) -> Self {
+        match self {
+            Dop2Error::InvalidConversion { type_name, detail, path } if path.is_empty() => Dop2Error::InvalidConversion {
+                type_name: struct_name,
+                detail: format!("{} into {}", detail, type_name),
+                path,
+            },
+            other => other,
+        }
+    }
+
+    /// Prefixes the path with the segment of the enclosing value
+    pub fn within(mut self, segment: Dop2PathSegment) -> Self {
+        self.path_mut().0.insert(0, segment);
+        self
+    }
+
+    pub fn path(&self) -> &Dop2Path {
+        match self {
+            Dop2Error::UnexpectedEof { path, .. }
+            | Dop2Error::UnknownTag { path, .. }
+            | Dop2Error::UnsupportedKind { path, .. }
+            | Dop2Error::InvalidValue { path, .. }
+            | Dop2Error::TrailingGarbage { path, .. }
+            | Dop2Error::LengthMismatch { path, .. }
+            | Dop2Error::LimitExceeded { path, .. }
+            | Dop2Error::InvalidPath { path, .. }
+            | Dop2Error::PathNotFound { path }
+            | Dop2Error::TypeMismatch { path, .. }
+            | Dop2Error::MissingField { path, .. }
+            | Dop2Error::InvalidConversion { path, .. } => path,
+        }
+    }
+
+    fn path_mut(&mut self) -> &mut Dop2Path {
+        match self {
+            Dop2Error::UnexpectedEof { path, .. }
+            | Dop2Error::UnknownTag { path, .. }
+            | Dop2Error::UnsupportedKind { path, .. }
+            | Dop2Error::InvalidValue { path, .. }
+            | Dop2Error::TrailingGarbage { path, .. }
+            | Dop2Error::LengthMismatch { path, .. }
+            | Dop2Error::LimitExceeded { path, .. }
+            | Dop2Error::InvalidPath { path, .. }
+            | Dop2Error::PathNotFound { path }
+            | Dop2Error::TypeMismatch { path, .. }
+            | Dop2Error::MissingField { path, .. }
+            | Dop2Error::InvalidConversion { path, .. } => path,
+        }
+    }
+
+    /// Byte offset into the frame, for errors raised while parsing bytes
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Dop2Error::UnexpectedEof { offset, .. }
+            | Dop2Error::UnknownTag { offset, .. }
+            | Dop2Error::UnsupportedKind { offset, .. }
+            | Dop2Error::InvalidValue { offset, .. }
+            | Dop2Error::TrailingGarbage { offset, .. }
+            | Dop2Error::LengthMismatch { offset, .. }
+            | Dop2Error::LimitExceeded { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Rust type under construction, for errors raised while converting a parse tree
+    pub fn type_name(&self) -> Option<&'static str> {
+        match self {
+            Dop2Error::TypeMismatch { type_name, .. }
+            | Dop2Error::MissingField { type_name, .. }
+            | Dop2Error::InvalidConversion { type_name, .. } => Some(type_name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Dop2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path().is_empty() {
+            write!(f, "{}: ", self.path())?;
+        }
+        match self {
+            Dop2Error::UnexpectedEof { offset, needed, available, .. } => write!(
+                f,
+                "needed {} bytes at offset {} but only {} remain",
+                needed, offset, available
+            ),
+            Dop2Error::UnknownTag { offset, tag, .. } => {
+                write!(f, "unknown type tag 0x{:02X} at offset {}", tag, offset)
+            }
+            Dop2Error::UnsupportedKind { offset, kind, .. } => {
+                write!(f, "unsupported payload kind {:?} at offset {}", kind, offset)
+            }
+            Dop2Error::InvalidValue { offset, kind, detail, .. } => {
+                write!(f, "invalid {:?} value {} at offset {}", kind, detail, offset)
+            }
+            Dop2Error::TrailingGarbage { offset, byte, .. } => {
+                write!(f, "non-padding byte 0x{:02X} at offset {}", byte, offset)
+            }
+            Dop2Error::LengthMismatch { offset, declared, actual, .. } => write!(
+                f,
+                "length word at offset {} declares {} bytes but the frame holds {}",
+                offset, declared, actual
+            ),
+            Dop2Error::LimitExceeded { offset, limit, value, max, .. } => write!(
+                f,
+                "{} {} at offset {} exceeds the limit of {}",
+                limit, value, offset, max
+            ),
+            Dop2Error::InvalidPath { text, .. } => write!(f, "invalid path {:?}", text),
+            Dop2Error::PathNotFound { .. } => write!(f, "no such field"),
+            Dop2Error::TypeMismatch { type_name, expected, found, .. } => write!(
+                f,
+                "expected {:?} but found {:?} while building {}",
+                expected, found, type_name
+            ),
+            Dop2Error::MissingField { type_name, expected, .. } => {
+                write!(f, "missing {:?} field while building {}", expected, type_name)
+            }
+            Dop2Error::InvalidConversion { type_name, detail, .. } => {
+                write!(f, "cannot convert {} while building {}", detail, type_name)
+            }
+        }
+    }
+}
+
+impl core::error::Error for Dop2Error {}
+
+/// For conversions that cannot fail, such as an enum into its `E8`
+impl From<core::convert::Infallible> for Dop2Error {
+    fn from(never: core::convert::Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// For a number that does not fit the integer type of the field it is read into
+impl From<core::num::TryFromIntError> for Dop2Error {
+    fn from(_: core::num::TryFromIntError) -> Self {
+        Dop2Error::invalid_conversion("the field type", "an integer out of range")
+    }
+}
+
+/// An anomaly that a lenient parser stepped over instead of failing on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dop2Warning {
+    /// What a strict parse would have failed with
+    pub error: Dop2Error,
+    /// Number of bytes kept verbatim because they could not be decoded
+    pub raw_bytes: usize,
+}
+
+impl Dop2Warning {
+    pub fn within(mut self, segment: Dop2PathSegment) -> Self {
+        self.error = self.error.within(segment);
+        self
+    }
+}
+
+impl fmt::Display for Dop2Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if self.raw_bytes > 0 {
+            write!(f, " (kept {} bytes as raw data)", self.raw_bytes)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+// DOP2 wire format: parser, parse tree and encoder.
+// Needs an allocator but not std, so it also runs on microcontroller bridges.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod error;
+pub mod notation;
+pub mod parser;
+pub mod root;
+pub mod types;
+mod hex;
+
+pub use error::{Dop2Error, Dop2Limit, Dop2Path, Dop2PathSegment, Dop2Warning};
+pub use parser::{Dop2Limits, Dop2Parser, Dop2PayloadExpressible, DopArray, ToDop2Bytes};
+pub use root::{
+    Dop2FieldInfo, Dop2Fields, Dop2ParseTreeExpressible, Dop2Payloads, Dop2PayloadsKind, Dop2Struct, DopPadding,
+    RootNode, TaggedDopField,
+};
+pub use types::{E8, E16, E32, E64};
@@ -0,0 +1,510 @@
+// Text notation for DOP2 parse trees, e.g. `2/1577 { 1: E16(13), 2: U16(0), 3: E8(1) }`
+//
+//   frame   := unit "/" attribute [ ":" idx1 "/" idx2 ] struct
+//   struct  := "{" [ field { "," field } [ "," ] ] "}"
+//   field   := index ":" payload
+//   payload := struct                       (MStruct)
+//            | Kind "(" value ")"           e.g. E8(4), I16(-3), F32(1.5), MString("abc"), Raw("0001ff")
+//            | ArrayKind "[" values "]"     e.g. ArrayU16[1, 2], AStruct[{ 1: E8(0) }]
+//
+// `{}` prints on one line, `{:#}` prints one field per line. Parsed frames have
+// a declared length of 0, so `to_bytes` computes it. Structs nest no deeper than
+// the default `Dop2Limits::max_depth`, the same as in frames off the wire.
+
+use crate::types::{E8, E16, E32, E64};
+use crate::parser::{Dop2Limits, Dop2PayloadExpressible, DopArray, ToDop2Bytes};
+use crate::root::{Dop2Payloads, Dop2PayloadsKind, Dop2Struct, RootNode, TaggedDopField};
+use num_enum::TryFromPrimitive;
+use ordered_float::OrderedFloat;
+use core::fmt;
+use core::str::FromStr;
+use alloc::{format, string::String, vec::Vec};
+
+/// A syntax error in DOP2 text notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dop2NotationError {
+    /// Byte offset into the text
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Dop2NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl core::error::Error for Dop2NotationError {}
+
+impl fmt::Display for RootNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.unit, self.attribute)?;
+        if self.idx1 != 0 || self.idx2 != 0 {
+            write!(f, ":{}/{}", self.idx1, self.idx2)?;
+        }
+        write!(f, " ")?;
+        self.root_struct.print(&mut Printer::new(f))
+    }
+}
+
+impl fmt::Display for Dop2Struct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.print(&mut Printer::new(f))
+    }
+}
+
+impl fmt::Display for Dop2Payloads {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Printer::new(f).payload(self)
+    }
+}
+
+impl FromStr for RootNode {
+    type Err = Dop2NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader::new(text);
+        let unit = reader.number()?;
+        reader.expect('/')?;
+        let attribute = reader.number()?;
+        let (mut idx1, mut idx2) = (0, 0);
+        if reader.consume(':') {
+            idx1 = reader.number()?;
+            reader.expect('/')?;
+            idx2 = reader.number()?;
+        }
+        let mut root_node = RootNode::single(unit, attribute, Dop2Struct::read(&mut reader)?);
+        root_node.idx1 = idx1;
+        root_node.idx2 = idx2;
+        reader.finish()?;
+        Ok(root_node)
+    }
+}
+
+impl FromStr for Dop2Struct {
+    type Err = Dop2NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader::new(text);
+        let root_struct = Dop2Struct::read(&mut reader)?;
+        reader.finish()?;
+        Ok(root_struct)
+    }
+}
+
+struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    pretty: bool,
+    depth: usize,
+}
+
+impl<'a, 'b> Printer<'a, 'b> {
+    fn new(f: &'a mut fmt::Formatter<'b>) -> Self {
+        let pretty = f.alternate();
+        Printer { f, pretty, depth: 0 }
+    }
+
+    fn indent(&mut self) -> fmt::Result {
+        write!(self.f, "{:1$}", "", self.depth * 4)
+    }
+
+    fn payload(&mut self, payload: &Dop2Payloads) -> fmt::Result {
+        let kind = Dop2PayloadsKind::from(payload);
+        match payload {
+            Dop2Payloads::Trash => write!(self.f, "{:?}", kind),
+            Dop2Payloads::Boolean(v) => self.scalar(kind, v),
+            Dop2Payloads::U8(v) => self.scalar(kind, v),
+            Dop2Payloads::I8(v) => self.scalar(kind, v),
+            Dop2Payloads::E8(v) => self.scalar(kind, v),
+            Dop2Payloads::U16(v) => self.scalar(kind, v),
+            Dop2Payloads::I16(v) => self.scalar(kind, v),
+            Dop2Payloads::E16(v) => self.scalar(kind, v),
+            Dop2Payloads::U32(v) => self.scalar(kind, v),
+            Dop2Payloads::I32(v) => self.scalar(kind, v),
+            Dop2Payloads::E32(v) => self.scalar(kind, v),
+            Dop2Payloads::U64(v) => self.scalar(kind, v),
+            Dop2Payloads::I64(v) => self.scalar(kind, v),
+            Dop2Payloads::E64(v) => self.scalar(kind, v),
+            Dop2Payloads::F32(v) => self.scalar(kind, v),
+            Dop2Payloads::F64(v) => self.scalar(kind, v),
+            Dop2Payloads::MString(v) => self.scalar(kind, v),
+            Dop2Payloads::MStruct(v) => v.print(self),
+            Dop2Payloads::ArrayBool(v) => self.array(kind, v),
+            Dop2Payloads::ArrayU8(v) => self.array(kind, v),
+            Dop2Payloads::ArrayI8(v) => self.array(kind, v),
+            Dop2Payloads::ArrayE8(v) => self.array(kind, v),
+            Dop2Payloads::ArrayU16(v) => self.array(kind, v),
+            Dop2Payloads::ArrayI16(v) => self.array(kind, v),
+            Dop2Payloads::ArrayE16(v) => self.array(kind, v),
+            Dop2Payloads::ArrayU32(v) => self.array(kind, v),
+            Dop2Payloads::ArrayI32(v) => self.array(kind, v),
+            Dop2Payloads::ArrayE32(v) => self.array(kind, v),
+            Dop2Payloads::ArrayU64(v) => self.array(kind, v),
+            Dop2Payloads::ArrayI64(v) => self.array(kind, v),
+            Dop2Payloads::ArrayE64(v) => self.array(kind, v),
+            Dop2Payloads::ArrayF32(v) => self.array(kind, v),
+            Dop2Payloads::ArrayF64(v) => self.array(kind, v),
+            Dop2Payloads::AStruct(v) => self.array(kind, v),
+            Dop2Payloads::Raw(bytes) => write!(self.f, "{:?}(\"{}\")", kind, crate::hex::encode(bytes)),
+        }
+    }
+
+    fn scalar<T: NotationValue>(&mut self, kind: Dop2PayloadsKind, value: &T) -> fmt::Result {
+        write!(self.f, "{:?}(", kind)?;
+        value.print(self)?;
+        write!(self.f, ")")
+    }
+
+    fn array<T>(&mut self, kind: Dop2PayloadsKind, array: &DopArray<T>) -> fmt::Result
+    where
+        T: NotationValue + Dop2PayloadExpressible + ToDop2Bytes,
+    {
+        write!(self.f, "{:?}[", kind)?;
+        for (i, element) in array.elements.iter().enumerate() {
+            if i > 0 {
+                write!(self.f, ", ")?;
+            }
+            element.print(self)?;
+        }
+        write!(self.f, "]")
+    }
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    position: usize,
+    /// Structs entered and not yet closed, checked against `max_depth`
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Self {
+        Reader {
+            text,
+            position: 0,
+            depth: 0,
+            max_depth: Dop2Limits::default().max_depth,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> Dop2NotationError {
+        Dop2NotationError {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.text[self.position..].chars().next()
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Dop2NotationError> {
+        if self.consume(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", expected)))
+        }
+    }
+
+    /// A run of characters that can make up a kind name or a number
+    fn token(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.' | '_')))
+            .unwrap_or(rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, Dop2NotationError> {
+        let start = self.position;
+        let token = self.token();
+        token.parse().map_err(|_| {
+            self.position = start;
+            self.error(format!("invalid {} '{}'", core::any::type_name::<T>(), token))
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String, Dop2NotationError> {
+        self.expect('"')?;
+        let mut result = String::new();
+        let mut chars = self.text[self.position..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(result);
+                }
+                '\\' => match chars.next() {
+                    Some((_, '"')) => result.push('"'),
+                    Some((_, '\\')) => result.push('\\'),
+                    Some((_, 'x')) => {
+                        let digits: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                        let byte = u8::from_str_radix(&digits, 16).map_err(|_| {
+                            self.position += offset;
+                            self.error("invalid \\x escape")
+                        })?;
+                        result.push(byte as char);
+                    }
+                    _ => {
+                        self.position += offset;
+                        return Err(self.error("invalid escape"));
+                    }
+                },
+                c => result.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn finish(&mut self) -> Result<(), Dop2NotationError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected input after the end of the frame")),
+        }
+    }
+
+    fn payload(&mut self) -> Result<Dop2Payloads, Dop2NotationError> {
+        if self.peek() == Some('{') {
+            return Ok(Dop2Payloads::MStruct(Dop2Struct::read(self)?));
+        }
+        let start = self.position;
+        let name = self.token();
+        let kind = (0..=u8::MAX)
+            .filter_map(|tag| Dop2PayloadsKind::try_from_primitive(tag).ok())
+            .find(|kind| format!("{:?}", kind) == name)
+            .ok_or_else(|| {
+                self.position = start;
+                self.error(format!("unknown payload kind '{}'", name))
+            })?;
+
+        Ok(match kind {
+            Dop2PayloadsKind::Boolean => Dop2Payloads::Boolean(self.scalar()?),
+            Dop2PayloadsKind::U8 => Dop2Payloads::U8(self.scalar()?),
+            Dop2PayloadsKind::I8 => Dop2Payloads::I8(self.scalar()?),
+            Dop2PayloadsKind::E8 => Dop2Payloads::E8(self.scalar()?),
+            Dop2PayloadsKind::U16 => Dop2Payloads::U16(self.scalar()?),
+            Dop2PayloadsKind::I16 => Dop2Payloads::I16(self.scalar()?),
+            Dop2PayloadsKind::E16 => Dop2Payloads::E16(self.scalar()?),
+            Dop2PayloadsKind::U32 => Dop2Payloads::U32(self.scalar()?),
+            Dop2PayloadsKind::I32 => Dop2Payloads::I32(self.scalar()?),
+            Dop2PayloadsKind::E32 => Dop2Payloads::E32(self.scalar()?),
+            Dop2PayloadsKind::U64 => Dop2Payloads::U64(self.scalar()?),
+            Dop2PayloadsKind::I64 => Dop2Payloads::I64(self.scalar()?),
+            Dop2PayloadsKind::E64 => Dop2Payloads::E64(self.scalar()?),
+            Dop2PayloadsKind::F32 => Dop2Payloads::F32(self.scalar()?),
+            Dop2PayloadsKind::F64 => Dop2Payloads::F64(self.scalar()?),
+            Dop2PayloadsKind::MString => Dop2Payloads::MString(self.scalar()?),
+            Dop2PayloadsKind::MStruct => Dop2Payloads::MStruct(Dop2Struct::read(self)?),
+            Dop2PayloadsKind::ArrayBool => Dop2Payloads::ArrayBool(self.array()?),
+            Dop2PayloadsKind::ArrayU8 => Dop2Payloads::ArrayU8(self.array()?),
+            Dop2PayloadsKind::ArrayI8 => Dop2Payloads::ArrayI8(self.array()?),
+            Dop2PayloadsKind::ArrayE8 => Dop2Payloads::ArrayE8(self.array()?),
+            Dop2PayloadsKind::ArrayU16 => Dop2Payloads::ArrayU16(self.array()?),
+            Dop2PayloadsKind::ArrayI16 => Dop2Payloads::ArrayI16(self.array()?),
+            Dop2PayloadsKind::ArrayE16 => Dop2Payloads::ArrayE16(self.array()?),
+            Dop2PayloadsKind::ArrayU32 => Dop2Payloads::ArrayU32(self.array()?),
+            Dop2PayloadsKind::ArrayI32 => Dop2Payloads::ArrayI32(self.array()?),
+            Dop2PayloadsKind::ArrayE32 => Dop2Payloads::ArrayE32(self.array()?),
+            Dop2PayloadsKind::ArrayU64 => Dop2Payloads::ArrayU64(self.array()?),
+            Dop2PayloadsKind::ArrayI64 => Dop2Payloads::ArrayI64(self.array()?),
+            Dop2PayloadsKind::ArrayE64 => Dop2Payloads::ArrayE64(self.array()?),
+            Dop2PayloadsKind::ArrayF32 => Dop2Payloads::ArrayF32(self.array()?),
+            Dop2PayloadsKind::ArrayF64 => Dop2Payloads::ArrayF64(self.array()?),
+            Dop2PayloadsKind::AStruct => Dop2Payloads::AStruct(self.array()?),
+            Dop2PayloadsKind::Raw => {
+                self.expect('(')?;
+                let text = self.quoted()?;
+                let bytes = crate::hex::decode(&text).ok_or_else(|| self.error("invalid hex in raw payload"))?;
+                self.expect(')')?;
+                Dop2Payloads::Raw(bytes)
+            }
+            Dop2PayloadsKind::Trash => return Err(self.error("Trash payloads cannot be written")),
+        })
+    }
+
+    fn scalar<T: NotationValue>(&mut self) -> Result<T, Dop2NotationError> {
+        self.expect('(')?;
+        let value = T::read(self)?;
+        self.expect(')')?;
+        Ok(value)
+    }
+
+    fn array<T>(&mut self) -> Result<DopArray<T>, Dop2NotationError>
+    where
+        T: NotationValue + Dop2PayloadExpressible + ToDop2Bytes,
+    {
+        self.expect('[')?;
+        let mut elements = Vec::new();
+        while !self.consume(']') {
+            elements.push(T::read(self)?);
+            if !self.consume(',') {
+                self.expect(']')?;
+                break;
+            }
+        }
+        let count = elements.len().try_into().map_err(|_| self.error("too many array elements"))?;
+        Ok(DopArray { count, elements })
+    }
+}
+
+/// A value that appears inside `Kind(..)` or as an array element
+trait NotationValue: Sized {
+    fn print(&self, printer: &mut Printer) -> fmt::Result;
+    fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError>;
+}
+
+macro_rules! notation_number {
+    ($($t:ty),*) => {
+        $(
+            impl NotationValue for $t {
+                fn print(&self, printer: &mut Printer) -> fmt::Result {
+                    write!(printer.f, "{}", self)
+                }
+
+                fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+                    reader.number()
+                }
+            }
+        )*
+    };
+}
+
+notation_number!(bool, u8, i8, u16, i16, u32, i32, u64, i64);
+
+macro_rules! notation_enum_number {
+    ($($t:ident),*) => {
+        $(
+            impl NotationValue for $t {
+                fn print(&self, printer: &mut Printer) -> fmt::Result {
+                    write!(printer.f, "{}", self.0)
+                }
+
+                fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+                    reader.number().map($t)
+                }
+            }
+        )*
+    };
+}
+
+notation_enum_number!(E8, E16, E32, E64);
+
+macro_rules! notation_float {
+    ($($t:ty),*) => {
+        $(
+            impl NotationValue for OrderedFloat<$t> {
+                fn print(&self, printer: &mut Printer) -> fmt::Result {
+                    // Debug keeps the decimal point on whole numbers, e.g. 1.0
+                    write!(printer.f, "{:?}", self.0)
+                }
+
+                fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+                    reader.number().map(OrderedFloat)
+                }
+            }
+        )*
+    };
+}
+
+notation_float!(f32, f64);
+
+impl NotationValue for String {
+    fn print(&self, printer: &mut Printer) -> fmt::Result {
+        write!(printer.f, "\"")?;
+        for c in self.chars() {
+            match c {
+                '"' => write!(printer.f, "\\\"")?,
+                '\\' => write!(printer.f, "\\\\")?,
+                ' '..='~' => write!(printer.f, "{}", c)?,
+                // strings are parsed one byte per char, so this covers every byte
+                c if (c as u32) <= 0xff => write!(printer.f, "\\x{:02x}", c as u32)?,
+                c => write!(printer.f, "{}", c)?,
+            }
+        }
+        write!(printer.f, "\"")
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+        reader.quoted()
+    }
+}
+
+impl NotationValue for Dop2Struct {
+    fn print(&self, printer: &mut Printer) -> fmt::Result {
+        if self.fields.is_empty() {
+            return write!(printer.f, "{{}}");
+        }
+        write!(printer.f, "{{")?;
+        printer.depth += 1;
+        for (i, field) in self.fields.iter().enumerate() {
+            if printer.pretty {
+                writeln!(printer.f)?;
+                printer.indent()?;
+            } else {
+                write!(printer.f, "{}", if i == 0 { " " } else { ", " })?;
+            }
+            write!(printer.f, "{}: ", field.field_index)?;
+            printer.payload(&field.value)?;
+            if printer.pretty {
+                write!(printer.f, ",")?;
+            }
+        }
+        printer.depth -= 1;
+        if printer.pretty {
+            writeln!(printer.f)?;
+            printer.indent()?;
+            write!(printer.f, "}}")
+        } else {
+            write!(printer.f, " }}")
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+        if reader.depth == reader.max_depth {
+            return Err(reader.error(format!("structs nested deeper than {}", reader.max_depth)));
+        }
+        reader.depth += 1;
+        let result = Dop2Struct::read_fields(reader);
+        reader.depth -= 1;
+        result
+    }
+}
+
+impl Dop2Struct {
+    fn read_fields(reader: &mut Reader) -> Result<Self, Dop2NotationError> {
+        reader.expect('{')?;
+        let mut fields = Vec::new();
+        while !reader.consume('}') {
+            let field_index = reader.number()?;
+            reader.expect(':')?;
+            let field = match reader.payload()? {
+                Dop2Payloads::Raw(bytes) => TaggedDopField::raw(bytes),
+                value => TaggedDopField::from_payload(field_index, value),
+            };
+            fields.push(field);
+            if !reader.consume(',') {
+                reader.expect('}')?;
+                break;
+            }
+        }
+        Dop2Struct::from_fields(fields).map_err(|e| reader.error(format!("{}", e)))
+    }
+}
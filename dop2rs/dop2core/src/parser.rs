@@ -0,0 +1,425 @@
+// Parser and core parsing infrastructure for DOP2 protocol
+
+use crate::error::{Dop2Error, Dop2Limit, Dop2PathSegment, Dop2Warning};
+use crate::root::Dop2PayloadsKind;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+
+/// Bounds on what a single frame may ask the parser to do.
+///
+/// The wire format trusts its own counts and nests structs without bound, so a
+/// parser reading bytes from the network should not take them at their word.
+/// The defaults accept every frame seen from real appliances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dop2Limits {
+    /// Structs inside structs, the root struct being depth 1
+    pub max_depth: usize,
+    /// Struct fields and array elements in one frame, as declared by their counts
+    pub max_elements: usize,
+    /// Bytes in one `MString`
+    pub max_string_length: usize,
+    /// Bytes in one frame, length word and padding included
+    pub max_frame_size: usize,
+}
+
+impl Dop2Limits {
+    /// The largest frame a u16 length word can describe, padding included
+    pub const MAX_FRAME_SIZE: usize = 2 + u16::MAX as usize + 15;
+
+    /// Limits that only the wire format itself imposes
+    pub fn unlimited() -> Self {
+        Dop2Limits {
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+            max_string_length: usize::MAX,
+            max_frame_size: usize::MAX,
+        }
+    }
+}
+
+impl Default for Dop2Limits {
+    fn default() -> Self {
+        Dop2Limits {
+            max_depth: 32,
+            max_elements: 65_536,
+            max_string_length: u16::MAX.into(),
+            max_frame_size: Self::MAX_FRAME_SIZE,
+        }
+    }
+}
+
+/// Parser for DOP2 protocol byte streams
+///
+/// Borrows the frame and advances a cursor over it, so reading a primitive
+/// never copies or shifts the remaining payload.
+///
+/// A lenient parser does not give up on bytes it cannot decode: the rest of
+/// the frame from the offending field onwards is kept as a `Dop2Payloads::Raw`
+/// field, unexpected bytes after the frame end up in `RootNode::trailing`, and
+/// every such anomaly is recorded in `warnings()`.
+///
+/// A strict parser fails with `Dop2Error::LengthMismatch` for a frame whose
+/// content disagrees with its length word. Either kind of parser fails with
+/// `Dop2Error::LimitExceeded` once a frame goes beyond its `Dop2Limits`.
+pub struct Dop2Parser<'a> {
+    payload: &'a [u8],
+    position: usize,
+    frame_end: usize,
+    lenient: bool,
+    raw_tail_taken: bool,
+    warnings: Vec<Dop2Warning>,
+    limits: Dop2Limits,
+    depth: usize,
+    elements: usize,
+}
+
+impl<'a> Dop2Parser<'a> {
+    pub fn new(payload: &'a [u8]) -> Self {
+        Self {
+            payload,
+            position: 0,
+            frame_end: payload.len(),
+            lenient: false,
+            raw_tail_taken: false,
+            warnings: Vec::new(),
+            limits: Dop2Limits::default(),
+            depth: 0,
+            elements: 0,
+        }
+    }
+
+    pub fn with_limits(payload: &'a [u8], limits: Dop2Limits) -> Self {
+        Self {
+            limits,
+            ..Self::new(payload)
+        }
+    }
+
+    /// Replaces the limits, e.g. of a lenient parser
+    pub fn set_limits(&mut self, limits: Dop2Limits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> &Dop2Limits {
+        &self.limits
+    }
+
+    pub fn lenient(payload: &'a [u8]) -> Self {
+        Self {
+            lenient: true,
+            ..Self::new(payload)
+        }
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Byte offset of the next unread byte, relative to the start of the frame
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.payload.len() - self.position
+    }
+
+    pub fn warnings(&self) -> &[Dop2Warning] {
+        &self.warnings
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<Dop2Warning> {
+        core::mem::take(&mut self.warnings)
+    }
+
+    pub(crate) fn warn(&mut self, warning: Dop2Warning) {
+        self.warnings.push(warning);
+    }
+
+    /// Adds the segment of the enclosing value to every warning raised since
+    /// `first`, the same way errors get their path while unwinding
+    pub(crate) fn prefix_warnings(&mut self, first: usize, segment: Dop2PathSegment) {
+        for warning in &mut self.warnings[first..] {
+            *warning = warning.clone().within(segment);
+        }
+    }
+
+    /// Marks where the frame that is currently being parsed ends, as declared by its length word
+    pub fn set_frame_end(&mut self, frame_end: usize) {
+        self.frame_end = frame_end.min(self.payload.len());
+        self.raw_tail_taken = false;
+        self.depth = 0;
+        self.elements = 0;
+    }
+
+    pub(crate) fn check_limit(&self, limit: Dop2Limit, offset: usize, value: usize) -> Result<(), Dop2Error> {
+        let max = match limit {
+            Dop2Limit::Depth => self.limits.max_depth,
+            Dop2Limit::Elements => self.limits.max_elements,
+            Dop2Limit::StringLength => self.limits.max_string_length,
+            Dop2Limit::FrameSize => self.limits.max_frame_size,
+        };
+        if value > max {
+            return Err(Dop2Error::LimitExceeded {
+                offset,
+                limit,
+                value,
+                max,
+                path: Default::default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Steps into a nested struct; every successful call is paired with `leave`
+    pub fn enter(&mut self, offset: usize) -> Result<(), Dop2Error> {
+        self.check_limit(Dop2Limit::Depth, offset, self.depth + 1)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Counts struct fields or array elements against the budget of the frame,
+    /// before anything is allocated for them
+    pub fn add_elements(&mut self, offset: usize, count: u16) -> Result<(), Dop2Error> {
+        let elements = self.elements.saturating_add(count.into());
+        self.check_limit(Dop2Limit::Elements, offset, elements)?;
+        self.elements = elements;
+        Ok(())
+    }
+
+    /// Moves the cursor back to a position returned by `position`, e.g. to parse a value twice
+    pub fn rewind(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Takes everything up to the end of the current frame as opaque bytes.
+    /// Enclosing structs and arrays stop reading once this has happened.
+    pub(crate) fn take_raw_tail(&mut self) -> &'a [u8] {
+        let end = self.frame_end.max(self.position);
+        let bytes = &self.payload[self.position..end];
+        self.position = end;
+        self.raw_tail_taken = true;
+        bytes
+    }
+
+    pub(crate) fn raw_tail_taken(&self) -> bool {
+        self.raw_tail_taken
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], Dop2Error> {
+        if self.remaining() < n {
+            return Err(Dop2Error::unexpected_eof(self.position, n, self.remaining()));
+        }
+        let bytes = &self.payload[self.position..self.position + n];
+        self.position += n;
+        Ok(bytes)
+    }
+
+    pub fn take_u16(&mut self) -> Result<u16, Dop2Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn take_u8(&mut self) -> Result<u8, Dop2Error> {
+        let bytes = self.take(1)?;
+        Ok(bytes[0])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// Trait for types that can be parsed from a DOP2 byte stream
+pub trait Dop2PayloadExpressible {
+    fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error>;
+}
+
+/// Trait for types that can be serialized to DOP2 bytes
+///
+/// Fails for values the wire format cannot hold, such as a string with
+/// characters beyond Latin-1 or more elements than a u16 count.
+pub trait ToDop2Bytes {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error>;
+}
+
+/// Array type for DOP2 protocol
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DopArray<T: Dop2PayloadExpressible + ToDop2Bytes> {
+    pub count: u16,
+    pub elements: Vec<T>,
+}
+
+/// Serialized as a plain list of its elements; the count is taken from its length
+impl<T> Serialize for DopArray<T>
+where
+    T: Dop2PayloadExpressible + ToDop2Bytes + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.elements.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for DopArray<T>
+where
+    T: Dop2PayloadExpressible + ToDop2Bytes + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = Vec::<T>::deserialize(deserializer)?;
+        let count = elements
+            .len()
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("too many array elements"))?;
+        Ok(DopArray { count, elements })
+    }
+}
+
+impl DopArray<u8> {
+    /// Converts the byte array to a hexadecimal string representation
+    pub fn to_hex_str(&self) -> String {
+        crate::hex::encode(&self.elements)
+    }
+}
+
+
+/// Element-wise conversion into typed values, e.g. an `AStruct` into `Vec<DeviceState>`
+impl<T, W> TryFrom<DopArray<W>> for Vec<T>
+where
+    W: Dop2PayloadExpressible + ToDop2Bytes,
+    T: TryFrom<W>,
+    T::Error: Into<Dop2Error>,
+{
+    type Error = Dop2Error;
+
+    fn try_from(value: DopArray<W>) -> Result<Self, Dop2Error> {
+        value
+            .elements
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| T::try_from(element).map_err(|e| e.into().within(Dop2PathSegment::Element(i))))
+            .collect()
+    }
+}
+
+/// Element-wise conversion of typed values, e.g. `Vec<ProcessState>` into an `ArrayE8`
+impl<T, W> TryFrom<Vec<T>> for DopArray<W>
+where
+    W: Dop2PayloadExpressible + ToDop2Bytes,
+    T: TryInto<W>,
+    T::Error: Into<Dop2Error>,
+{
+    type Error = Dop2Error;
+
+    fn try_from(value: Vec<T>) -> Result<Self, Dop2Error> {
+        let elements = value
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| element.try_into().map_err(|e| e.into().within(Dop2PathSegment::Element(i))))
+            .collect::<Result<Vec<W>, Dop2Error>>()?;
+        let count = elements
+            .len()
+            .try_into()
+            .map_err(|_| Dop2Error::invalid_conversion("DopArray", "more than 65535 elements"))?;
+        Ok(DopArray { count, elements })
+    }
+}
+
+impl<T: Dop2PayloadExpressible + ToDop2Bytes> Dop2PayloadExpressible for DopArray<T> {
+    fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+        let offset = parser.position();
+        let count = parser.take_u16()?;
+        parser.add_elements(offset, count)?;
+        let mut elements: Vec<T> = Vec::new();
+        for index in 0..count {
+            if parser.raw_tail_taken() {
+                break;
+            }
+            let first_warning = parser.warnings().len();
+            let element = T::parse(parser).map_err(|e| e.within(Dop2PathSegment::Element(index.into())))?;
+            parser.prefix_warnings(first_warning, Dop2PathSegment::Element(index.into()));
+            elements.push(*element);
+        }
+        Ok(Box::new(DopArray { count, elements }))
+    }
+}
+
+impl<T: Dop2PayloadExpressible + ToDop2Bytes> ToDop2Bytes for DopArray<T> {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        vec.extend(self.count.to_be_bytes());
+        for (index, element) in self.elements.into_iter().enumerate() {
+            element.to_bytes(vec).map_err(|e| e.within(Dop2PathSegment::Element(index)))?;
+        }
+        Ok(())
+    }
+}
+
+// Implementations for primitive types
+
+impl Dop2PayloadExpressible for bool {
+    fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+        let offset = parser.position();
+        let payload_byte = parser.take_u8()?;
+        if payload_byte >= 0x02 {
+            return Err(Dop2Error::InvalidValue {
+                offset,
+                kind: Dop2PayloadsKind::Boolean,
+                detail: format!("0x{:02X}", payload_byte),
+                path: Default::default(),
+            });
+        }
+        Ok(Box::new(payload_byte == 0x01))
+    }
+}
+
+impl ToDop2Bytes for bool {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        if self {
+            vec.push(0x01);
+        } else {
+            vec.push(0x00);
+        }
+        Ok(())
+    }
+}
+
+
+impl Dop2PayloadExpressible for String {
+    fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+        let offset = parser.position();
+        let length = parser.take_u16()?;
+        parser.check_limit(Dop2Limit::StringLength, offset, length.into())?;
+        let mut result = String::new();
+        let string_bytes = parser.take(length.into())?;
+        for &b in string_bytes {
+            // ASCII byte → char conversion
+            result.push(b as char);
+        }
+        Ok(Box::new(result))
+    }
+}
+
+impl ToDop2Bytes for String {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        // inverse of the byte → char mapping in parse, so every byte survives a round trip
+        let ascii = self
+            .chars()
+            .map(|c| {
+                u8::try_from(c).map_err(|_| {
+                    Dop2Error::invalid_conversion("MString", format!("'{}' (U+{:04X}), which is not Latin-1", c, u32::from(c)))
+                })
+            })
+            .collect::<Result<Vec<u8>, Dop2Error>>()?;
+        let length: u16 = ascii.len().try_into().map_err(|_| {
+            Dop2Error::invalid_conversion("MString", format!("a string of {} bytes, more than its length word holds", ascii.len()))
+        })?;
+        vec.extend(length.to_be_bytes());
+        vec.extend(ascii);
+        Ok(())
+    }
+}
+
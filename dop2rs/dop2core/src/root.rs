@@ -0,0 +1,805 @@
+// Root node and payload structures for DOP2 protocol
+
+use crate::parser::{Dop2Parser, Dop2PayloadExpressible, ToDop2Bytes, DopArray};
+use crate::error::{Dop2Error, Dop2Limit, Dop2Path, Dop2PathSegment, Dop2Warning};
+use crate::types::{E8, E16, E32, E64};
+use enum_kinds::EnumKind;
+use num_enum::TryFromPrimitive;
+use ordered_float::OrderedFloat;
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use core::fmt;
+use alloc::{boxed::Box, format, string::{String, ToString}, vec::Vec};
+
+/// Root node structure for DOP2 protocol
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RootNode {
+    pub unit: u16,
+    pub attribute: u16,
+    /// Length word as parsed. `to_bytes` computes it from the content unless a lenient
+    /// parse found the frame disagreeing with its own length word, which is then written back verbatim.
+    #[serde(default)]
+    pub declared_length: u16,
+    #[serde(default)]
+    pub idx1: u16,
+    #[serde(default)]
+    pub idx2: u16,
+    pub root_struct: Dop2Struct,
+    /// Bytes after the root struct that are not canonical padding, kept by a lenient parse
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "crate::hex")]
+    pub trailing: Vec<u8>,
+    #[serde(skip)]
+    keep_declared_length: bool,
+    /// Set when the parsed frame had no padding at all, which `trailing` cannot tell apart from the default
+    #[serde(skip)]
+    keep_trailing: bool,
+}
+
+impl RootNode {
+    pub fn single(unit: u16, attribute: u16, root_struct: Dop2Struct) -> RootNode {
+        RootNode {
+            unit,
+            attribute,
+            declared_length: 0,
+            idx1: 0,
+            idx2: 0,
+            root_struct,
+            trailing: Vec::new(),
+            keep_declared_length: false,
+            keep_trailing: false,
+        }
+    }
+
+    /// See `Dop2Struct::get`
+    pub fn get(&self, path: &str) -> Option<&Dop2Payloads> {
+        self.root_struct.get(path)
+    }
+
+    /// See `Dop2Struct::set`
+    pub fn set(&mut self, path: &str, value: Dop2Payloads) -> Result<Option<Dop2Payloads>, Dop2Error> {
+        self.root_struct.set(path, value)
+    }
+
+    pub fn has_more_siblings(&self) -> bool {
+        return self.idx1 == self.idx2;
+    }
+
+    pub fn parse(parser: &mut Dop2Parser) -> Result<RootNode, Dop2Error> {
+        let start = parser.position();
+        let first_warning = parser.warnings().len();
+        let declared_length = parser.take_u16()?; // only for validation, not needed for further parsing
+        parser.set_frame_end(start + 2 + usize::from(declared_length));
+        let content_length = 2 + usize::from(declared_length);
+        let frame_size = content_length + usize::from(DopPadding::after(content_length).bytes_of_padding);
+        parser.check_limit(Dop2Limit::FrameSize, start, frame_size)?;
+
+        let unit = parser.take_u16()?;
+        let attribute = parser.take_u16()?;
+        let in_node = |e: Dop2Error| {
+            e.within(Dop2PathSegment::Attribute(attribute))
+                .within(Dop2PathSegment::Unit(unit))
+        };
+
+        let idx1 = parser.take_u16().map_err(in_node)?;
+        let idx2 = parser.take_u16().map_err(in_node)?;
+
+        let root_struct = *Dop2Struct::parse(parser).map_err(in_node)?;
+
+        let actual = parser.position() - start - 2;
+        let keep_declared_length = actual != usize::from(declared_length);
+        let mismatch = Dop2Error::LengthMismatch {
+            offset: start,
+            declared: declared_length,
+            actual,
+            path: Default::default(),
+        };
+        // a frame longer than its length word read into whatever follows it
+        if keep_declared_length && !parser.is_lenient() {
+            return Err(in_node(mismatch));
+        }
+        let trailing = if parser.is_lenient() {
+            if keep_declared_length {
+                parser.check_limit(Dop2Limit::FrameSize, start, 2 + actual)?;
+                parser.warn(Dop2Warning { error: mismatch, raw_bytes: 0 });
+            }
+            DopPadding::parse_lenient(parser, start)
+        } else {
+            let content_length = parser.position() - start;
+            let padding = DopPadding::take_padding(parser).map_err(in_node)?;
+            match padding.len() == usize::from(DopPadding::after(content_length).bytes_of_padding) {
+                true => None,
+                false => Some(padding.to_vec()),
+            }
+        };
+        parser.prefix_warnings(first_warning, Dop2PathSegment::Attribute(attribute));
+        parser.prefix_warnings(first_warning, Dop2PathSegment::Unit(unit));
+
+        Ok(RootNode {
+            unit,
+            attribute,
+            declared_length,
+            idx1,
+            idx2,
+            root_struct,
+            keep_trailing: trailing.is_some(),
+            trailing: trailing.unwrap_or_default(),
+            keep_declared_length,
+        })
+    }
+
+    /// Appends the frame, padding included. Fails if a value does not fit the wire format.
+    pub fn to_bytes(self, builder: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        let (unit, attribute) = (self.unit, self.attribute);
+        let in_node = |e: Dop2Error| {
+            e.within(Dop2PathSegment::Attribute(attribute))
+                .within(Dop2PathSegment::Unit(unit))
+        };
+        let mut content: Vec<u8> = Vec::new();
+        content.extend(self.unit.to_be_bytes());
+        content.extend(self.attribute.to_be_bytes());
+
+        content.extend(self.idx1.to_be_bytes());
+        content.extend(self.idx2.to_be_bytes());
+
+        self.root_struct.to_bytes(&mut content).map_err(in_node)?;
+        let length: u16 = match self.keep_declared_length {
+            true => self.declared_length,
+            false => content.len().try_into().map_err(|_| {
+                in_node(Dop2Error::invalid_conversion(
+                    "RootNode",
+                    format!("{} bytes of content, more than the length word holds", content.len()),
+                ))
+            })?,
+        };
+        builder.extend(length.to_be_bytes());
+        let content_length = content.len() + 2;
+        builder.extend(content);
+
+        if self.trailing.is_empty() && !self.keep_trailing {
+            DopPadding::after(content_length).to_bytes(builder)
+        } else {
+            builder.extend(self.trailing);
+            Ok(())
+        }
+    }
+}
+
+/// Tagged field structure for DOP2 protocol
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaggedDopField {
+    pub tag: Dop2PayloadsKind,
+    pub field_index: u16,
+    pub value: Dop2Payloads,
+}
+
+impl TaggedDopField {
+    /// Field number and type tag in front of the payload
+    const HEADER_LENGTH: u16 = 3;
+
+    pub fn from_payload(field_index: u16, value: Dop2Payloads) -> TaggedDopField {
+        let tag = Dop2PayloadsKind::from(&value);
+        return TaggedDopField {
+            field_index,
+            tag,
+            value,
+        };
+    }
+
+    /// Wraps bytes that a lenient parse could not decode, starting at the field header
+    pub fn raw(bytes: Vec<u8>) -> TaggedDopField {
+        let field_index = match bytes.as_slice() {
+            [high, low, ..] => u16::from_be_bytes([*high, *low]),
+            _ => 0,
+        };
+        TaggedDopField {
+            field_index,
+            tag: Dop2PayloadsKind::Raw,
+            value: Dop2Payloads::Raw(bytes),
+        }
+    }
+
+    pub fn get_length(&self) -> u16 {
+        Self::HEADER_LENGTH
+    }
+
+    pub fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        if let Dop2Payloads::Raw(bytes) = self.value {
+            // the raw bytes already include the field header
+            vec.extend(bytes);
+            return Ok(());
+        }
+        vec.extend(self.field_index.to_be_bytes());
+        vec.push(self.tag as u8);
+
+        let field_index = self.field_index;
+        match self.value {
+            Dop2Payloads::Boolean(b) => b.to_bytes(vec),
+            Dop2Payloads::U8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::U16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::U32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::U64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::I8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::I16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::I32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::I64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::E8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::E16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::E32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::E64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::MString(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayU8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayU16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayI8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayI16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayI32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayE8(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayE16(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayE32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayE64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayU32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayU64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::MStruct(payload) => payload.to_bytes(vec),
+            Dop2Payloads::AStruct(payload) => payload.to_bytes(vec),
+            Dop2Payloads::F32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::F64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayBool(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayI64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayF32(payload) => payload.to_bytes(vec),
+            Dop2Payloads::ArrayF64(payload) => payload.to_bytes(vec),
+            Dop2Payloads::Raw(_) => unreachable!(),
+            Dop2Payloads::Trash => Err(Dop2Error::invalid_conversion("Dop2Payloads", "a Trash payload, which has no wire encoding")),
+        }
+        .map_err(|e| e.within(Dop2PathSegment::Field(field_index)))
+    }
+
+    pub fn parse(parser: &mut Dop2Parser) -> Result<TaggedDopField, Dop2Error> {
+        let field_index = parser.take_u16()?;
+        let first_warning = parser.warnings().len();
+        let field = Self::parse_value(parser, field_index)
+            .map_err(|e| e.within(Dop2PathSegment::Field(field_index)))?;
+        parser.prefix_warnings(first_warning, Dop2PathSegment::Field(field_index));
+        Ok(field)
+    }
+
+    fn parse_value(parser: &mut Dop2Parser, field_index: u16) -> Result<TaggedDopField, Dop2Error> {
+        let offset = parser.position();
+        let tag_byte = parser.take_u8()?;
+        let tag = Dop2PayloadsKind::try_from_primitive(tag_byte)
+            .ok()
+            .filter(|kind| *kind != Dop2PayloadsKind::Raw) // not a wire tag
+            .ok_or(Dop2Error::UnknownTag {
+                offset,
+                tag: tag_byte,
+                path: Default::default(),
+            })?;
+        let value = match tag {
+            Dop2PayloadsKind::Boolean => Dop2Payloads::Boolean(*bool::parse(parser)?),
+            Dop2PayloadsKind::E8 => Dop2Payloads::E8(*E8::parse(parser)?),
+            Dop2PayloadsKind::U8 => Dop2Payloads::U8(*u8::parse(parser)?),
+            Dop2PayloadsKind::U16 => Dop2Payloads::U16(*u16::parse(parser)?),
+            Dop2PayloadsKind::U64 => Dop2Payloads::U64(*u64::parse(parser)?),
+            Dop2PayloadsKind::I8 => Dop2Payloads::I8(*i8::parse(parser)?),
+            Dop2PayloadsKind::I16 => Dop2Payloads::I16(*i16::parse(parser)?),
+            Dop2PayloadsKind::E16 => Dop2Payloads::E16(*E16::parse(parser)?),
+            Dop2PayloadsKind::U32 => Dop2Payloads::U32(*u32::parse(parser)?),
+            Dop2PayloadsKind::I32 => Dop2Payloads::I32(*i32::parse(parser)?),
+            Dop2PayloadsKind::E32 => Dop2Payloads::E32(*E32::parse(parser)?),
+            Dop2PayloadsKind::I64 => Dop2Payloads::I64(*i64::parse(parser)?),
+            Dop2PayloadsKind::E64 => Dop2Payloads::E64(*E64::parse(parser)?),
+            Dop2PayloadsKind::F32 => Dop2Payloads::F32(*OrderedFloat::<f32>::parse(parser)?),
+            Dop2PayloadsKind::F64 => Dop2Payloads::F64(*OrderedFloat::<f64>::parse(parser)?),
+            Dop2PayloadsKind::MString => Dop2Payloads::MString(*String::parse(parser)?),
+            Dop2PayloadsKind::ArrayBool => Dop2Payloads::ArrayBool(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayU8 => Dop2Payloads::ArrayU8(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayI8 => Dop2Payloads::ArrayI8(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayI16 => Dop2Payloads::ArrayI16(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayI32 => Dop2Payloads::ArrayI32(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayU32 => Dop2Payloads::ArrayU32(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayU16 => Dop2Payloads::ArrayU16(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayE8 => Dop2Payloads::ArrayE8(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayE16 => Dop2Payloads::ArrayE16(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayE32 => Dop2Payloads::ArrayE32(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayE64 => Dop2Payloads::ArrayE64(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayU64 => Dop2Payloads::ArrayU64(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayI64 => Dop2Payloads::ArrayI64(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayF32 => Dop2Payloads::ArrayF32(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::ArrayF64 => Dop2Payloads::ArrayF64(*DopArray::parse(parser)?),
+            Dop2PayloadsKind::MStruct => Dop2Payloads::MStruct(*Dop2Struct::parse(parser)?),
+            Dop2PayloadsKind::AStruct => Dop2Payloads::AStruct(*DopArray::parse(parser)?),
+
+            unsupported => {
+                return Err(Dop2Error::UnsupportedKind {
+                    offset,
+                    kind: unsupported,
+                    path: Default::default(),
+                });
+            }
+        };
+
+        Ok(TaggedDopField { tag, field_index, value })
+    }
+}
+
+/// DOP2 payloads enum
+///
+/// Serializes externally tagged by kind, e.g. `{"E8": 4}` or `{"ArrayU16": [1, 2]}`
+#[derive(Clone, Debug, PartialEq, Eq, EnumKind, Serialize, Deserialize)]
+#[enum_kind(Dop2PayloadsKind, derive(TryFromPrimitive), repr(u8))]
+pub enum Dop2Payloads {
+    /// Tag 0, which no frame carries and nothing can write, so it is kept out of JSON too
+    #[serde(skip)]
+    Trash,
+    Boolean(bool), // 1
+    U8(u8),        // 2
+    I8(i8),
+    E8(E8),
+    U16(u16),
+    I16(i16),
+    E16(E16),
+    U32(u32),
+    I32(i32),
+    E32(E32),
+    U64(u64),
+    I64(i64),
+    E64(E64),
+    F32(OrderedFloat<f32>),
+    F64(OrderedFloat<f64>),
+    MStruct(Dop2Struct),
+    ArrayBool(DopArray<bool>),
+    ArrayU8(DopArray<u8>),
+    ArrayI8(DopArray<i8>),
+    ArrayE8(DopArray<E8>),
+    ArrayU16(DopArray<u16>),
+    ArrayI16(DopArray<i16>),
+    ArrayE16(DopArray<E16>),
+    ArrayU32(DopArray<u32>),
+    ArrayI32(DopArray<i32>),
+    ArrayE32(DopArray<u32>),
+    ArrayU64(DopArray<u64>),
+    ArrayI64(DopArray<i64>),
+    ArrayE64(DopArray<u64>),
+    ArrayF32(DopArray<OrderedFloat<f32>>),
+    ArrayF64(DopArray<OrderedFloat<f64>>),
+    MString(String),
+    AStruct(DopArray<Dop2Struct>),
+    /// Undecodable bytes kept by a lenient parse, see `TaggedDopField::raw`
+    Raw(#[serde(with = "crate::hex")] Vec<u8>),
+}
+
+impl Dop2PayloadsKind {
+    /// Whether the payload starts with a u16 element count
+    pub fn is_array(self) -> bool {
+        let tag = self as u8;
+        (Dop2PayloadsKind::ArrayBool as u8..=Dop2PayloadsKind::ArrayF64 as u8).contains(&tag)
+            || self == Dop2PayloadsKind::AStruct
+    }
+}
+
+/// The fields of a struct that its Rust type does not hold, kept by `#[dop2(extra)]`:
+/// fields without a `#[dop2field]` and values that did not decode into theirs.
+///
+/// It also remembers the order the struct was read in, so a write puts every
+/// field back where it was, whatever the order of the field numbers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dop2ExtraFields {
+    pub fields: Vec<TaggedDopField>,
+    /// Field numbers of the struct as read, the ones the Rust type holds included
+    order: Vec<u16>,
+}
+
+impl Dop2ExtraFields {
+    /// `fields` taken out of `read`
+    pub fn read(read: &Dop2Struct, fields: Vec<TaggedDopField>) -> Self {
+        Dop2ExtraFields {
+            fields,
+            order: read.fields.iter().map(|field| field.field_index).collect(),
+        }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, TaggedDopField> {
+        self.fields.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Puts the fields the Rust type holds and the extra ones together in the order they
+    /// were read; a field of the Rust type replaces an extra one with the same number.
+    /// Fields that were not read follow, by field number.
+    pub fn merge(&self, held: Vec<TaggedDopField>) -> Vec<TaggedDopField> {
+        fn take<T>(fields: &mut [Option<T>], index: u16, field_index: impl Fn(&T) -> u16) -> Option<T> {
+            fields.iter_mut().find(|field| field.as_ref().is_some_and(|field| field_index(field) == index))?.take()
+        }
+        let mut held: Vec<Option<TaggedDopField>> = held.into_iter().map(Some).collect();
+        let mut extra: Vec<Option<&TaggedDopField>> = self.fields.iter().map(Some).collect();
+        let mut merged = Vec::new();
+        for &index in &self.order {
+            let held = take(&mut held, index, |field| field.field_index);
+            let extra = take(&mut extra, index, |field| field.field_index);
+            merged.extend(held.or(extra.cloned()));
+        }
+        let mut rest: Vec<TaggedDopField> = held.into_iter().flatten().chain(extra.into_iter().flatten().cloned()).collect();
+        rest.sort_by_key(|field| field.field_index);
+        merged.extend(rest);
+        merged
+    }
+}
+
+impl From<Vec<TaggedDopField>> for Dop2ExtraFields {
+    /// Extra fields of a value that was not read, written by field number
+    fn from(fields: Vec<TaggedDopField>) -> Self {
+        Dop2ExtraFields { fields, order: Vec::new() }
+    }
+}
+
+/// DOP2 struct structure
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dop2Struct {
+    pub declared_fields: u16,
+    pub fields: Vec<TaggedDopField>,
+}
+
+impl Dop2Struct {
+    pub fn get_field(&self, id: u16) -> Option<TaggedDopField> {
+        self.field(id).cloned()
+    }
+
+    pub fn get_payload(&self, id: u16) -> Option<Dop2Payloads> {
+        self.field(id).map(|x| x.value.clone())
+    }
+
+    /// The first field with the given index
+    pub fn field(&self, id: u16) -> Option<&TaggedDopField> {
+        self.fields.iter().find(|x| x.field_index == id)
+    }
+
+    fn field_mut(&mut self, id: u16) -> Option<&mut TaggedDopField> {
+        self.fields.iter_mut().find(|x| x.field_index == id)
+    }
+
+    /// Payload at a path in query notation like `7.21.2` or `4[2].5`.
+    /// Returns `None` for malformed paths and paths that lead nowhere.
+    pub fn get(&self, path: &str) -> Option<&Dop2Payloads> {
+        self.get_path(&path.parse().ok()?)
+    }
+
+    /// Payload at a path, e.g. the path of a `Dop2Error`. Leading unit and
+    /// attribute segments are skipped, the path must end at a field.
+    pub fn get_path(&self, path: &Dop2Path) -> Option<&Dop2Payloads> {
+        let (&last, parents) = Self::inside_struct(path).split_last()?;
+        let Dop2PathSegment::Field(index) = last else {
+            return None;
+        };
+        self.struct_at(parents)?.field(index).map(|field| &field.value)
+    }
+
+    /// Replaces the payload at `path` and returns the previous one. A missing
+    /// last field is appended to its struct, everything before it must exist.
+    pub fn set(&mut self, path: &str, value: Dop2Payloads) -> Result<Option<Dop2Payloads>, Dop2Error> {
+        self.set_path(&path.parse()?, value)
+    }
+
+    pub fn set_path(&mut self, path: &Dop2Path, value: Dop2Payloads) -> Result<Option<Dop2Payloads>, Dop2Error> {
+        let not_found = || Dop2Error::PathNotFound { path: path.clone() };
+        let Some((&Dop2PathSegment::Field(index), parents)) = Self::inside_struct(path).split_last() else {
+            return Err(not_found());
+        };
+        let parent = self.struct_at_mut(parents).ok_or_else(not_found)?;
+        match parent.field_mut(index) {
+            Some(field) => {
+                field.tag = Dop2PayloadsKind::from(&value);
+                Ok(Some(core::mem::replace(&mut field.value, value)))
+            }
+            None => {
+                parent.declared_fields = parent
+                    .declared_fields
+                    .checked_add(1)
+                    .ok_or_else(|| Self::too_many_fields(parent.fields.len() + 1))?;
+                parent.fields.push(TaggedDopField::from_payload(index, value));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Every payload that is not a struct or struct array, with its path, in wire order
+    pub fn leaves(&self) -> Vec<(Dop2Path, &Dop2Payloads)> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut Vec::new(), &mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, prefix: &mut Vec<Dop2PathSegment>, leaves: &mut Vec<(Dop2Path, &'a Dop2Payloads)>) {
+        for field in &self.fields {
+            prefix.push(Dop2PathSegment::Field(field.field_index));
+            match &field.value {
+                Dop2Payloads::MStruct(inner) => inner.collect_leaves(prefix, leaves),
+                Dop2Payloads::AStruct(array) => {
+                    for (i, element) in array.elements.iter().enumerate() {
+                        prefix.push(Dop2PathSegment::Element(i));
+                        element.collect_leaves(prefix, leaves);
+                        prefix.pop();
+                    }
+                }
+                value => leaves.push((Dop2Path(prefix.clone()), value)),
+            }
+            prefix.pop();
+        }
+    }
+
+    fn inside_struct(path: &Dop2Path) -> &[Dop2PathSegment] {
+        let segments = path.segments();
+        let header = segments
+            .iter()
+            .take_while(|s| matches!(s, Dop2PathSegment::Unit(_) | Dop2PathSegment::Attribute(_)))
+            .count();
+        &segments[header..]
+    }
+
+    /// The struct reached by following `segments`, each field step into an
+    /// `MStruct` or, followed by an element step, into an `AStruct`
+    fn struct_at(&self, segments: &[Dop2PathSegment]) -> Option<&Dop2Struct> {
+        match segments {
+            [] => Some(self),
+            [Dop2PathSegment::Field(i), Dop2PathSegment::Element(n), rest @ ..] => match &self.field(*i)?.value {
+                Dop2Payloads::AStruct(array) => array.elements.get(*n)?.struct_at(rest),
+                _ => None,
+            },
+            [Dop2PathSegment::Field(i), rest @ ..] => match &self.field(*i)?.value {
+                Dop2Payloads::MStruct(inner) => inner.struct_at(rest),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn struct_at_mut(&mut self, segments: &[Dop2PathSegment]) -> Option<&mut Dop2Struct> {
+        match segments {
+            [] => Some(self),
+            [Dop2PathSegment::Field(i), Dop2PathSegment::Element(n), rest @ ..] => match &mut self.field_mut(*i)?.value {
+                Dop2Payloads::AStruct(array) => array.elements.get_mut(*n)?.struct_at_mut(rest),
+                _ => None,
+            },
+            [Dop2PathSegment::Field(i), rest @ ..] => match &mut self.field_mut(*i)?.value {
+                Dop2Payloads::MStruct(inner) => inner.struct_at_mut(rest),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// A struct of the given fields, which a count word can hold at most 65535 of
+    pub fn from_fields(fields: Vec<TaggedDopField>) -> Result<Self, Dop2Error> {
+        let declared_fields = fields.len().try_into().map_err(|_| Self::too_many_fields(fields.len()))?;
+        Ok(Dop2Struct { declared_fields, fields })
+    }
+
+    fn too_many_fields(count: usize) -> Dop2Error {
+        Dop2Error::LimitExceeded {
+            offset: 0,
+            limit: Dop2Limit::Elements,
+            value: count,
+            max: usize::from(u16::MAX),
+            path: Default::default(),
+        }
+    }
+
+    pub fn get_length(&self) -> Option<u16> {
+        self.fields.iter().try_fold(2u16, |length, field| length.checked_add(field.get_length()))
+    }
+}
+
+impl ToDop2Bytes for Dop2Struct {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        vec.extend(self.declared_fields.to_be_bytes());
+        self.fields.into_iter().try_for_each(|field| field.to_bytes(vec))
+    }
+}
+
+/// A field serializes as a single-entry map from its index to its payload, `{"1": {"E8": 4}}`
+impl Serialize for TaggedDopField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.field_index.to_string(), &self.value)?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TaggedDopField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = deserializer.deserialize_map(FieldMapVisitor)?;
+        match (fields.pop(), fields.is_empty()) {
+            (Some(field), true) => Ok(field),
+            _ => Err(serde::de::Error::custom("expected exactly one field")),
+        }
+    }
+}
+
+/// A struct serializes as a map from field index to payload, in wire order
+impl Serialize for Dop2Struct {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in &self.fields {
+            map.serialize_entry(&field.field_index.to_string(), &field.value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Dop2Struct {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = deserializer.deserialize_map(FieldMapVisitor)?;
+        Dop2Struct::from_fields(fields).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Reads `{"<index>": <payload>, ...}` keeping the order of the entries
+struct FieldMapVisitor;
+
+impl<'de> Visitor<'de> for FieldMapVisitor {
+    type Value = Vec<TaggedDopField>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a map from field index to payload")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let field_index = key
+                .parse::<u16>()
+                .map_err(|_| serde::de::Error::custom(format!("invalid field index {:?}", key)))?;
+            let value: Dop2Payloads = map.next_value()?;
+            let field = match value {
+                Dop2Payloads::Raw(bytes) => TaggedDopField::raw(bytes),
+                value => TaggedDopField::from_payload(field_index, value),
+            };
+            fields.push(field);
+        }
+        Ok(fields)
+    }
+}
+
+impl Dop2PayloadExpressible for Dop2Struct {
+    fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+        let offset = parser.position();
+        parser.enter(offset)?;
+        let result = Dop2Struct::parse_fields(parser);
+        parser.leave();
+        result.map(Box::new)
+    }
+}
+
+impl Dop2Struct {
+    fn parse_fields(parser: &mut Dop2Parser) -> Result<Dop2Struct, Dop2Error> {
+        let offset = parser.position();
+        let declared_fields = parser.take_u16()?;
+        parser.add_elements(offset, declared_fields)?;
+        let mut fields = Vec::new();
+        for _x in 0..declared_fields {
+            if parser.raw_tail_taken() {
+                break;
+            }
+            let field_start = parser.position();
+            match TaggedDopField::parse(parser) {
+                Ok(tagged_field) => fields.push(tagged_field),
+                // running into a limit is a reason to stop, not an anomaly to step over
+                Err(error) if parser.is_lenient() && !matches!(error, Dop2Error::LimitExceeded { .. }) => {
+                    parser.rewind(field_start);
+                    let raw = parser.take_raw_tail().to_vec();
+                    parser.warn(Dop2Warning { error, raw_bytes: raw.len() });
+                    fields.push(TaggedDopField::raw(raw));
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(Dop2Struct {
+            declared_fields,
+            fields,
+        })
+    }
+}
+
+/// Padding structure for DOP2 protocol
+#[derive(Debug)]
+pub struct DopPadding {
+    pub bytes_of_padding: u8,
+}
+
+impl DopPadding {
+    const PADDING_BYTE: u8 = 0x20;
+    const PADDING_ALIGNMENT: u16 = 0x10;
+
+    pub fn minimum_padding(builder: &Vec<u8>) -> DopPadding {
+        DopPadding::after(builder.len())
+    }
+
+    /// Padding that aligns a frame of `content_length` bytes, length word included
+    pub fn after(content_length: usize) -> DopPadding {
+        let alignment = usize::from(DopPadding::PADDING_ALIGNMENT);
+        DopPadding {
+            bytes_of_padding: ((alignment - content_length % alignment) % alignment) as u8,
+        }
+    }
+
+    /// Consumes the rest of the buffer, which must be padding bytes only.
+    /// More than 255 of them are counted as 255.
+    pub fn parse(parser: &mut Dop2Parser) -> Result<DopPadding, Dop2Error> {
+        let padding = DopPadding::take_padding(parser)?;
+        Ok(DopPadding {
+            bytes_of_padding: padding.len().try_into().unwrap_or(u8::MAX),
+        })
+    }
+
+    fn take_padding<'a>(parser: &mut Dop2Parser<'a>) -> Result<&'a [u8], Dop2Error> {
+        let offset = parser.position();
+        let rest = parser.take(parser.remaining())?;
+        if let Some(garbage) = rest.iter().position(|b| *b != DopPadding::PADDING_BYTE) {
+            return Err(Dop2Error::TrailingGarbage {
+                offset: offset + garbage,
+                byte: rest[garbage],
+                path: Default::default(),
+            });
+        }
+        Ok(rest)
+    }
+
+    /// Consumes the rest of the buffer after the root struct of the frame starting at
+    /// `frame_start`. Returns nothing if it is exactly the minimum padding, otherwise
+    /// all of it so that it can be written back verbatim.
+    fn parse_lenient(parser: &mut Dop2Parser, frame_start: usize) -> Option<Vec<u8>> {
+        let expected_padding = DopPadding::after(parser.position() - frame_start).bytes_of_padding;
+        let offset = parser.position();
+        let rest = parser.take(parser.remaining()).unwrap_or_default();
+
+        if let Some(garbage) = rest.iter().position(|b| *b != DopPadding::PADDING_BYTE) {
+            parser.warn(Dop2Warning {
+                error: Dop2Error::TrailingGarbage {
+                    offset: offset + garbage,
+                    byte: rest[garbage],
+                    path: Default::default(),
+                },
+                raw_bytes: rest.len(),
+            });
+        } else if rest.len() == usize::from(expected_padding) {
+            return None;
+        }
+        Some(rest.to_vec())
+    }
+}
+
+impl ToDop2Bytes for DopPadding {
+    fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+        vec.extend(
+            core::iter::repeat(DopPadding::PADDING_BYTE).take(self.bytes_of_padding.into()),
+        );
+        Ok(())
+    }
+}
+
+/// Trait for types that can be created from a DOP2 parse tree
+pub trait Dop2ParseTreeExpressible: Sized {
+    fn from_parse_tree(payload: Dop2Payloads) -> Result<Self, Dop2Error>;
+}
+
+/// Describes one `#[dop2field]` of a Rust struct
+#[derive(Debug, Clone, Copy)]
+pub struct Dop2FieldInfo {
+    pub index: u16,
+    pub name: &'static str,
+    pub kind: Dop2PayloadsKind,
+    /// Fields of the struct type behind an `MStruct` or `AStruct` field
+    pub nested: Option<&'static [Dop2FieldInfo]>,
+}
+
+/// Field layout of a Rust struct, generated by `#[derive(AssocTypes)]`
+pub trait Dop2Fields {
+    const DOP2_FIELDS: &'static [Dop2FieldInfo];
+}
+
+
+
@@ -0,0 +1,125 @@
+// Wire encoding of the scalar types and the E8..E64 enum value newtypes
+
+use crate::error::Dop2Error;
+use crate::parser::{Dop2Parser, Dop2PayloadExpressible, DopArray, ToDop2Bytes};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use ordered_float::OrderedFloat;
+
+macro_rules! impl_int_bytes {
+    ($t:ty) => {
+        impl ToDop2Bytes for $t {
+            fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+                vec.extend(self.to_be_bytes());
+                Ok(())
+            }
+        }
+
+        impl Dop2PayloadExpressible for $t {
+            fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+                let bytes = parser.take(core::mem::size_of::<$t>())?;
+                let value = <$t>::from_be_bytes(bytes.try_into().unwrap());
+
+                Ok(Box::new(value))
+            }
+        }
+    };
+}
+
+// Floats travel as big-endian IEEE 754, like every other DOP2 scalar. They are
+// wrapped in OrderedFloat so the parse tree can keep deriving Eq.
+macro_rules! impl_float_bytes {
+    ($t:ty) => {
+        impl ToDop2Bytes for OrderedFloat<$t> {
+            fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+                vec.extend(self.0.to_be_bytes());
+                Ok(())
+            }
+        }
+
+        impl Dop2PayloadExpressible for OrderedFloat<$t> {
+            fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+                let bytes = parser.take(core::mem::size_of::<$t>())?;
+                let value = <$t>::from_be_bytes(bytes.try_into().unwrap());
+
+                Ok(Box::new(OrderedFloat(value)))
+            }
+        }
+    };
+}
+
+/// Enum values travel as plain unsigned integers; the newtypes keep them apart
+/// from counts and measurements in the parse tree
+macro_rules! newtype_int {
+    ($name:ident, $inner:ty) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                $name(value)
+            }
+        }
+
+        impl ToDop2Bytes for $name {
+            fn to_bytes(self, vec: &mut Vec<u8>) -> Result<(), Dop2Error> {
+                vec.extend(self.0.to_be_bytes());
+                Ok(())
+            }
+        }
+
+        impl Dop2PayloadExpressible for $name {
+            fn parse(parser: &mut Dop2Parser) -> Result<Box<Self>, Dop2Error> {
+                let bytes = parser.take(core::mem::size_of::<$inner>())?;
+                let value = <$inner>::from_be_bytes(bytes.try_into().unwrap());
+
+                Ok(Box::new(value.into()))
+            }
+        }
+    };
+}
+
+impl_int_bytes!(u8);
+impl_int_bytes!(i8);
+impl_int_bytes!(u16);
+impl_int_bytes!(i16);
+impl_int_bytes!(u32);
+impl_int_bytes!(i32);
+impl_int_bytes!(u64);
+impl_int_bytes!(i64);
+
+impl_float_bytes!(f32);
+impl_float_bytes!(f64);
+
+newtype_int!(E8, u8);
+newtype_int!(E16, u16);
+newtype_int!(E32, u32);
+newtype_int!(E64, u64);
+
+impl TryFrom<DopArray<u8>> for String {
+    type Error = Dop2Error;
+
+    fn try_from(value: DopArray<u8>) -> Result<Self, Self::Error> {
+        // Convert only up to `count` elements.
+        if value.count > value.elements.len() as u16 {
+            return Err(Dop2Error::invalid_conversion("String", "count field exceeds number of elements in DopArray<u8>"));
+        }
+        let ascii_bytes = &value.elements[..value.count as usize];
+        match core::str::from_utf8(ascii_bytes) {
+            Ok(s) => Ok(s.to_string()),
+            Err(_) => Err(Dop2Error::invalid_conversion("String", alloc::format!("invalid ASCII/UTF-8 {}", value.to_hex_str()))),
+        }
+    }
+}
+
+impl From<String> for DopArray<u8> {
+    fn from(value: String) -> Self {
+        let ascii_bytes = value.as_bytes();
+        DopArray {
+            count: ascii_bytes.len() as u16,
+            elements: ascii_bytes.to_vec(),
+        }
+    }
+}
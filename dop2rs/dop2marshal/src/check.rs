@@ -0,0 +1,114 @@
+// Checks of a `#[dop2field]` that can be made before type checking
+
+use syn::{GenericArgument, Path, PathArguments, Type, TypePath};
+
+/// Every variant of `Dop2Payloads` that a field can be declared as
+const KINDS: &[&str] = &[
+    "Boolean", "U8", "I8", "E8", "U16", "I16", "E16", "U32", "I32", "E32", "U64", "I64", "E64", "F32", "F64",
+    "MStruct", "ArrayBool", "ArrayU8", "ArrayI8", "ArrayE8", "ArrayU16", "ArrayI16", "ArrayE16", "ArrayU32",
+    "ArrayI32", "ArrayE32", "ArrayU64", "ArrayI64", "ArrayE64", "ArrayF32", "ArrayF64", "MString", "AStruct",
+];
+
+/// Primitive Rust types and the one scalar kind each of them is read from
+const PRIMITIVES: &[(&str, &str)] = &[
+    ("bool", "Boolean"),
+    ("u8", "U8"),
+    ("i8", "I8"),
+    ("u16", "U16"),
+    ("i16", "I16"),
+    ("u32", "U32"),
+    ("i32", "I32"),
+    ("u64", "U64"),
+    ("i64", "I64"),
+];
+
+/// The payload kind a `#[dop2field]` names, e.g. `U16` for `Dop2Payloads::U16`
+pub fn kind_name(path: &Path) -> syn::Result<String> {
+    let kind = match path.segments.last() {
+        Some(segment) => segment.ident.to_string(),
+        None => return Err(syn::Error::new_spanned(path, "payload type path must not be empty")),
+    };
+    if !KINDS.contains(&kind.as_str()) {
+        return Err(syn::Error::new_spanned(
+            path,
+            format!("`{}` is not a Dop2Payloads variant, expected one like `Dop2Payloads::U16`", kind),
+        ));
+    }
+    Ok(kind)
+}
+
+/// Rejects a Rust field type that cannot hold a payload of the declared kind
+pub fn field_fits_kind(ty: &Type, kind: &str) -> syn::Result<()> {
+    let inner = strip_wrapper(ty, "Option").unwrap_or(ty);
+    let mismatch = |expected: &str| {
+        Err(syn::Error::new_spanned(
+            ty,
+            format!("a `Dop2Payloads::{}` field needs {}, not `{}`", kind, expected, type_name(inner)),
+        ))
+    };
+
+    let is_array_kind = kind.starts_with("Array") || kind == "AStruct";
+    if let Some((_, primitive_kind)) = PRIMITIVES.iter().find(|(name, _)| is_ident(inner, name)) {
+        if kind != *primitive_kind {
+            return mismatch(&expected_for(kind));
+        }
+    } else if is_ident(inner, "String") {
+        if kind != "MString" && kind != "ArrayU8" {
+            return mismatch(&expected_for(kind));
+        }
+    } else if let Some(element) = strip_wrapper(inner, "DopArray") {
+        let element_kind = PRIMITIVES.iter().find(|(name, _)| is_ident(element, name)).map(|(_, kind)| *kind);
+        match element_kind {
+            Some(element_kind) if kind != format!("Array{}", element_kind) => return mismatch(&expected_for(kind)),
+            None if !is_array_kind => return mismatch(&expected_for(kind)),
+            _ => {}
+        }
+    } else if strip_wrapper(inner, "Vec").is_some() && !is_array_kind {
+        return mismatch(&expected_for(kind));
+    }
+    Ok(())
+}
+
+/// What the error message suggests for a kind
+fn expected_for(kind: &str) -> String {
+    if let Some((name, _)) = PRIMITIVES.iter().find(|(_, primitive_kind)| *primitive_kind == kind) {
+        return format!("`{}` or a type converting from it", name);
+    }
+    match kind {
+        "MString" => "`String`".to_string(),
+        "MStruct" => "a struct deriving AssocTypes".to_string(),
+        "AStruct" => "a `Vec` of structs deriving AssocTypes".to_string(),
+        "E8" | "E16" | "E32" | "E64" => format!("`{}` or an enum deriving Dop2Enum", kind),
+        _ => match kind.strip_prefix("Array") {
+            Some(element) => match PRIMITIVES.iter().find(|(_, primitive_kind)| *primitive_kind == element) {
+                Some((name, _)) => format!("`DopArray<{}>` or a `Vec` of a type converting from `{}`", name, name),
+                None => format!("a `Vec` of a type converting from `{}`", element),
+            },
+            None => format!("a type converting from `{}`", kind),
+        },
+    }
+}
+
+fn is_ident(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Path(TypePath { qself: None, path }) if path.is_ident(name))
+}
+
+/// `T` of `Wrapper<T>`
+fn strip_wrapper<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(TypePath { path, .. }) = ty else { return None };
+    let segment = path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    quote::quote!(#ty).to_string().replace(' ', "")
+}
@@ -0,0 +1,147 @@
+// Conversions between an enum of named values and its E8/E16 wire value
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit, Meta};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let enum_name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(syn::Error::new_spanned(enum_name, "Dop2Enum can only be derived for enums")),
+    };
+    let wire = wire_type(input)?;
+
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    let mut unknown = None;
+    // value of the next variant without an explicit one, counted like the compiler does
+    let mut next = Some(0u64);
+    for variant in &data.variants {
+        match &variant.fields {
+            Fields::Unit => {
+                let value = match &variant.discriminant {
+                    Some((_, Expr::Lit(ExprLit { lit: Lit::Int(int), .. }))) => int.base10_parse::<u64>()?,
+                    Some((_, other)) => {
+                        return Err(syn::Error::new_spanned(other, "Dop2Enum values must be integer literals"));
+                    }
+                    None => next.ok_or_else(|| syn::Error::new_spanned(variant, "Dop2Enum cannot count past this value"))?,
+                };
+                let value = u16::try_from(value)
+                    .map_err(|_| syn::Error::new_spanned(variant, "Dop2Enum values must fit into a u16"))?;
+                next = u64::from(value).checked_add(1);
+                names.push(&variant.ident);
+                values.push(value);
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 && unknown.is_none() => {
+                unknown = Some(&variant.ident);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "Dop2Enum variants are fieldless, apart from one catch-all like `Unknown(u16)`",
+                ));
+            }
+        }
+    }
+    let unknown = unknown.ok_or_else(|| {
+        syn::Error::new_spanned(enum_name, "Dop2Enum needs an `Unknown(u16)` variant for values without a name")
+    })?;
+
+    // E8 enums can hold a catch-all value above 255 that does not fit back on the wire
+    let to_wire = if wire == "E8" {
+        quote! {
+            impl TryFrom<#enum_name> for E8 {
+                type Error = Dop2Error;
+
+                fn try_from(value: #enum_name) -> Result<E8, Dop2Error> {
+                    let raw = value.raw();
+                    u8::try_from(raw).map(E8).map_err(|_| {
+                        Dop2Error::invalid_conversion("E8", format!("{}::{}({})", stringify!(#enum_name), stringify!(#unknown), raw))
+                    })
+                }
+            }
+
+            impl From<E8> for #enum_name {
+                fn from(value: E8) -> #enum_name {
+                    #enum_name::from(u16::from(value.0))
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl From<#enum_name> for E16 {
+                fn from(value: #enum_name) -> E16 {
+                    E16(value.raw())
+                }
+            }
+
+            impl From<E16> for #enum_name {
+                fn from(value: E16) -> #enum_name {
+                    #enum_name::from(value.0)
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #enum_name {
+            /// The value on the wire, including that of the catch-all variant
+            pub fn raw(&self) -> u16 {
+                match self {
+                    #( #enum_name::#names => #values, )*
+                    #enum_name::#unknown(raw) => *raw,
+                }
+            }
+
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, #enum_name::#unknown(_))
+            }
+        }
+
+        impl From<u16> for #enum_name {
+            fn from(raw: u16) -> #enum_name {
+                match raw {
+                    #( #values => #enum_name::#names, )*
+                    raw => #enum_name::#unknown(raw),
+                }
+            }
+        }
+
+        impl From<#enum_name> for u16 {
+            fn from(value: #enum_name) -> u16 {
+                value.raw()
+            }
+        }
+
+        #to_wire
+    })
+}
+
+/// `E8` or `E16` from `#[dop2(wire = E16)]`
+fn wire_type(input: &DeriveInput) -> syn::Result<Ident> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("dop2") {
+            continue;
+        }
+        if let Meta::List(_) = &attr.meta {
+            let mut wire = None;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("wire") {
+                    let ident: Ident = meta.value()?.parse()?;
+                    if ident != "E8" && ident != "E16" {
+                        return Err(syn::Error::new_spanned(&ident, "Dop2Enum supports `wire = E8` and `wire = E16`"));
+                    }
+                    wire = Some(ident);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `wire = E8` or `wire = E16`"))
+                }
+            })?;
+            if let Some(wire) = wire {
+                return Ok(wire);
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(&input.ident, "Dop2Enum needs the wire type, e.g. #[dop2(wire = E16)]"))
+}
@@ -1,19 +1,40 @@
+mod check;
+mod dop2enum;
+mod root;
+
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, punctuated::Punctuated, DeriveInput, Expr, ExprLit, Lit, Type, token::Comma, ExprPath, TypePath};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_macro_input, punctuated::Punctuated, spanned::Spanned, DeriveInput, Expr, ExprLit, Lit, Type, token::Comma, ExprPath, TypePath};
 
-#[proc_macro_derive(AssocTypes, attributes(dop2field))]
+/// Conversions between a struct and its DOP2 parse tree, one `#[dop2field(index, Dop2Payloads::Kind)]` per field.
+///
+/// A value that does not convert into the field type is an error naming the struct and
+/// the field. `Option` fields are `None` when the field is missing or of another kind; with
+/// `#[dop2field(index, Dop2Payloads::Kind, lenient)]` a value that does not convert is `None` as well.
+///
+/// Field numbers must be unique, and a primitive field type must match its kind
+/// (`u16` for `U16`, `DopArray<u8>` for `ArrayU8`), which is checked when deriving.
+///
+/// A `Dop2ExtraFields` marked `#[dop2(extra)]` keeps the fields that have no `#[dop2field]`
+/// and the values of `Option` fields that stayed `None`, and writes them back in the order
+/// they were read, so nothing is lost on a round trip.
+///
+/// A struct that is the root of a frame names where it lives with
+/// `#[dop2(unit = 14, attribute = 130)]` (or `attribute = [131, 392]`, or
+/// `leaves = [(1, 391), (2, 1585)]` for one found at several units), which registers
+/// it with every `AttributeRegistry` by unit and attribute and adds `to_root_node`.
+#[proc_macro_derive(AssocTypes, attributes(dop2field, dop2))]
 pub fn derive_assoc_types(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let struct_name = input.ident;
+    let struct_name = &input.ident;
 
 //    let trait_ident = format_ident!("Dop2ParseTreeExpressible");
     let marker_prefix = format!("__AssocFor_{}_field_", struct_name);
 
     // Only support named-structs
-    let fields = match input.data {
-        syn::Data::Struct(s) => match s.fields {
-            syn::Fields::Named(named) => named.named,
+    let fields = match &input.data {
+        syn::Data::Struct(s) => match &s.fields {
+            syn::Fields::Named(named) => &named.named,
             _ => {
                 return syn::Error::new_spanned(
                     struct_name,
@@ -34,13 +55,46 @@ pub fn derive_assoc_types(input: TokenStream) -> TokenStream {
     let mut constructor_fragments = Vec::new();
 
     let mut marshalling_field_definitions = Vec::new();
+    let mut field_infos = Vec::new();
+    let mut getters = Vec::new();
+    let mut constructor_params = Vec::new();
+    let mut constructor_fields = Vec::new();
+    let all_fields_public = fields.iter().all(|field| matches!(field.vis, syn::Visibility::Public(_)));
+    // field numbers seen so far and the Rust field that uses them
+    let mut numbers: Vec<(u16, &syn::Ident)> = Vec::new();
+    // the `#[dop2(extra)]` field that keeps fields without a `#[dop2field]`
+    let mut extra_field: Option<(&syn::Ident, &Type)> = None;
+    // Option fields, whose value is kept as extra when it did not decode
+    let mut option_fields: Vec<(syn::LitInt, &syn::Ident)> = Vec::new();
 
     for field in fields.iter() {
         let field_ident = match &field.ident {
             Some(id) => id,
             None => continue,
         };
-        
+        let field_ty = &field.ty;
+        constructor_params.push(quote!(#field_ident: #field_ty));
+        constructor_fields.push(quote!(#field_ident));
+        if !matches!(field.vis, syn::Visibility::Public(_)) {
+            getters.push(quote! {
+                pub fn #field_ident(&self) -> &#field_ty {
+                    &self.#field_ident
+                }
+            });
+        }
+
+        match extra_attribute(field) {
+            Ok(false) => {}
+            Ok(true) if extra_field.is_some() => {
+                return syn::Error::new_spanned(field, "only one field can be #[dop2(extra)]").to_compile_error().into();
+            }
+            Ok(true) => {
+                extra_field = Some((field_ident, &field.ty));
+                continue;
+            }
+            Err(e) => return e.to_compile_error().into(),
+        }
+
         // Find an attribute named "assoc_type"
         for attr in &field.attrs {
             if attr.path().is_ident("dop2field") { // only parse our attribute
@@ -57,36 +111,67 @@ pub fn derive_assoc_types(input: TokenStream) -> TokenStream {
                     }
                 };
 
-                if args.len() != 2 {
+                if args.len() != 2 && args.len() != 3 {
                     return syn::Error::new_spanned(
                         attr,
-                        "expected exactly two arguments: #[dop2field(fieldId, payloadType)]",
+                        "expected #[dop2field(fieldId, payloadType)] or #[dop2field(fieldId, payloadType, lenient)]",
                     )
                     .to_compile_error()
                     .into();
                 }
 
+                // `lenient` turns a value of an Option field that does not convert into None
+                let lenient = match args.get(2) {
+                    None => false,
+                    Some(Expr::Path(ExprPath { path, .. })) if path.is_ident("lenient") => true,
+                    Some(other) => {
+                        return syn::Error::new_spanned(other, "the only option after the payload type is `lenient`")
+                            .to_compile_error()
+                            .into();
+                    }
+                };
+
                 let enum_expr = match &args[1] {
                     Expr::Path(ExprPath { path, .. }) => path.clone(),
                     _ => {
-                        return syn::Error::new_spanned(&args[1], 
-                            "third argument must be a path expression like MyEnum::Variant")
+                        return syn::Error::new_spanned(&args[1],
+                            "second argument must be the payload type, like Dop2Payloads::U16")
                             .to_compile_error()
                             .into();
                     }
                 };
 
+                let kind_name = match check::kind_name(&enum_expr) {
+                    Ok(kind_name) => kind_name,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let kind_ident = format_ident!("{}", kind_name);
+                let expected_kind = quote!(Dop2PayloadsKind::#kind_ident);
+                if let Err(e) = check::field_fits_kind(&field.ty, &kind_name) {
+                    return e.to_compile_error().into();
+                }
+
                 let number = match &args[0] {
                     Expr::Lit(ExprLit { lit: Lit::Int(litint), .. }) => litint,
                     _ => {
                         return syn::Error::new_spanned(
                             &args[0],
-                            "third argument must be an integer literal",
+                            "first argument must be the field number as an integer literal",
                         )
                         .to_compile_error()
                         .into();
                     }
                 };
+                let value = match number.base10_parse::<u16>() {
+                    Ok(value) => value,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                if let Some((_, other)) = numbers.iter().find(|(seen, _)| *seen == value) {
+                    return syn::Error::new_spanned(number, format!("field number {} is already used by `{}`", value, other))
+                        .to_compile_error()
+                        .into();
+                }
+                numbers.push((value, field_ident));
 
 //let in_ty: Type = syn::parse2(args[1].to_token_stream()).unwrap();
 //let out_ty: Type = syn::parse2(args[1].to_token_stream()).unwrap();
@@ -97,6 +182,18 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
     false
 };
 
+                let field_name = field_ident.to_string();
+                let nested = match kind_name.as_str() {
+                    "MStruct" | "AStruct" => {
+                        let inner = innermost_type(&field.ty);
+                        quote!(Some(<#inner as Dop2Fields>::DOP2_FIELDS))
+                    }
+                    _ => quote!(None),
+                };
+                field_infos.push(quote! {
+                    Dop2FieldInfo { index: #number, name: #field_name, kind: #expected_kind, nested: #nested }
+                });
+
                 let marker_ident = format_ident!("{}{}", marker_prefix, field_ident);
                 let marshaling_payload_ident = format_ident!("{}{}", "_payload_", field_ident);
                 let marshaling_field_ident = format_ident!("{}{}", "_field_", field_ident);
@@ -109,59 +206,118 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
                 {
                     constructor_fragments.push(quote!{,});
                 }
-                if is_option
+                // spanned at the field type, so a type that does not convert is reported there
+                let convert = quote_spanned! {field_ty.span()=>
+                    unwrapped.try_into().map_err(|e| Dop2Error::from(e).in_struct(stringify!(#struct_name)))
+                };
+                if is_option {
+                    option_fields.push((number.clone(), field_ident));
+                }
+                if lenient && !is_option {
+                    return syn::Error::new_spanned(&args[2], "`lenient` only applies to Option fields")
+                        .to_compile_error()
+                        .into();
+                }
+                if is_option && !lenient
+                {
+                    constructor_fragments.push(quote! {
+                        #field_ident: match x.get_payload(#number)
+                        {
+                          Some(#enum_expr(unwrapped)) => #convert.map(Some),
+                          // a payload of another kind is not this field
+                          _ => Ok(None)
+                        }.map_err(|e: Dop2Error| e.within(Dop2PathSegment::Field(#number)))?
+                    })
+                }
+                else if is_option
                 {
-                    
-                    /*impls.push(quote! {
-                        let #enum_expr(#field_ident) = x.get_payload(#number) && 
-                    });*/
-                   
                     constructor_fragments.push(quote! {
                        #field_ident: match x.get_payload(#number)
                        {
-                         Some(test) => match test {
-                            #enum_expr(unwrapped) => Some(unwrapped.try_into().unwrap()),
-                            _ => None
+                         Some(#enum_expr(unwrapped)) => {
+                            let converted: Result<_, Dop2Error> = #convert;
+                            converted.ok()
                          },
-                         None => None
+                         _ => None
                        }
                     })
-
                 }
-                else 
-                {
-                    //println!("field_ident: {:?} is not an option", stringify!(#field_ident));
-               
-                
+                else
                 {
                     constructor_fragments.push(quote! {
                         #field_ident: match x.get_payload(#number)
                         {
                           Some(test) => match test {
-                             #enum_expr(unwrapped) => Ok(unwrapped.try_into().unwrap()),
-                             _ => Err(format!("failed converting type: field #{} {} is invalid in struct {}", #number, stringify!(#field_ident), stringify!(#struct_name)))
+                             #enum_expr(unwrapped) => #convert,
+                             other => Err(Dop2Error::type_mismatch(stringify!(#struct_name), #expected_kind, Dop2PayloadsKind::from(&other)))
                           },
-                          None => Err(format!("failed converting type: field #{} {} is missing from payload in struct {}", #number, stringify!(#field_ident), stringify!(#struct_name)))
-                        }?
-                        
+                          None => Err(Dop2Error::missing_field(stringify!(#struct_name), #expected_kind))
+                        }.map_err(|e: Dop2Error| e.within(Dop2PathSegment::Field(#number)))?
                      });
-                marshalling_field_definitions.push(quote!( { 
-               //     let marshaling_payload_ident : Dop2Payloads = Dop2Payloads::U16(self.selection_parameter.clone().into());
-                 //   let marshaling_payload_ident = (self.#field_ident).clone(); // this works
-                    //if (let Some(unwrapped_value) = self.#field_ident) 
-                    { // skip the field if it's None
-                    let #marshaling_payload_ident : Dop2Payloads = #enum_expr (self.#field_ident.clone().try_into().unwrap()).clone().into(); // this works
-                    let #marshaling_field_ident : TaggedDopField = TaggedDopField { field_index: #number, tag: Dop2PayloadsKind::from(#marshaling_payload_ident.clone()), value: #marshaling_payload_ident};
-                    fields.push(#marshaling_field_ident);
-                    }
-                
-                  //  let #marshaling_field_ident = 3;
-                } ));
-
                 }
+
+                // Option fields are written when Some and left out when None
+                let value = if is_option {
+                    quote!(self.#field_ident.clone())
+                } else {
+                    quote!(Some(self.#field_ident.clone()))
+                };
+                let convert_back = quote_spanned! {field_ty.span()=>
+                    value.try_into()
+                        .map_err(|e| Dop2Error::from(e).in_struct(stringify!(#struct_name)).within(Dop2PathSegment::Field(#number)))?
+                };
+                marshalling_field_definitions.push(quote! {
+                    if let Some(value) = #value {
+                        let converted = #convert_back;
+                        let #marshaling_payload_ident : Dop2Payloads = #enum_expr (converted);
+                        let #marshaling_field_ident : TaggedDopField = TaggedDopField { field_index: #number, tag: Dop2PayloadsKind::from(&#marshaling_payload_ident), value: #marshaling_payload_ident};
+                        fields.push(#marshaling_field_ident);
+                    }
+                });
             }
         } }
-    }
+
+    let root = match root::expand(&input, &numbers) {
+        Ok(root) => root,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    // unmodelled fields and Option values that did not decode go back where they were read
+    let (extra_read, extra_marshal) = match extra_field {
+        Some((extra, extra_ty)) => {
+            let known = numbers.iter().map(|(number, _)| number);
+            let (option_numbers, option_idents): (Vec<_>, Vec<_>) = option_fields.iter().cloned().unzip();
+            if !constructor_fragments.is_empty() {
+                constructor_fragments.push(quote! {,});
+            }
+            constructor_fragments.push(quote! {
+                #extra: Default::default()
+            });
+            let read = quote! {
+                y.#extra = <#extra_ty>::read(&x, x.fields.iter().filter(|field| match field.field_index {
+                    #( #option_numbers => y.#option_idents.is_none(), )*
+                    index => ![#( #known ),*].contains(&index)
+                }).cloned().collect());
+            };
+            let marshal = quote! {
+                let fields = self.#extra.merge(fields);
+            };
+            (read, marshal)
+        }
+        None => (quote!(), quote!()),
+    };
+
+    // structs with non-public fields can only be built from outside the crate through `new`
+    let constructor = if all_fields_public {
+        quote!()
+    } else {
+        quote! {
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(#( #constructor_params ),*) -> Self {
+                Self { #( #constructor_fields ),* }
+            }
+        }
+    };
 
     // Emit the trait, markers and impls
     let expanded = quote! {
@@ -170,9 +326,18 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
 //            type Ty;
  //       }
  //payloads::impl_tryfrom_dop2struct!(#struct_name);
+         impl #struct_name
+         {
+            #constructor
+
+            #( #getters )*
+         }
+
          impl #struct_name 
          {
-            pub fn to_dop2_struct_auto (&self) -> Result<Dop2Struct, String>
+            // conversions are spanned at the field types, which are often the payload types themselves
+            #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
+            pub fn to_dop2_struct_auto (&self) -> Result<Dop2Struct, Dop2Error>
             {
                 let mut fields: Vec<TaggedDopField> = vec!();
 
@@ -190,46 +355,39 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
                 fields.push(selection_type_field);
                 Ok(Dop2Struct::from_fields (fields))
                  */
+                 #extra_marshal
 
-                 Ok(Dop2Struct::from_fields (fields))
+                 Dop2Struct::from_fields (fields)
             }
          }
 
+         #root
+
+         impl Dop2Fields for #struct_name
+         {
+            const DOP2_FIELDS: &'static [Dop2FieldInfo] = &[ #( #field_infos ),* ];
+         }
+
          impl TryInto<Dop2Struct> for #struct_name
          { 
-            type Error = String;
-            fn try_into(self)-> Result<Dop2Struct, String>
+            type Error = Dop2Error;
+            fn try_into(self)-> Result<Dop2Struct, Dop2Error>
             {
                 return self.to_dop2_struct_auto();
             }
          }
 
-         impl TryInto<DopArray<Dop2Struct>> for Vec<#struct_name>
-         {
-             type Error = String;
-         
-             fn try_into(self) -> Result<DopArray<Dop2Struct>, String> {
-                //let elements : Vec<Dop2Struct> = self.into_iter().map(|e| e.try_into().unwrap()).collect(); // TODO: Figure out error handling
-                    // value.elements.into_iter().map(|garbage|#struct_name::try_into(garbage)).collect()
-                     
-                    Ok(DopArray {
-                        count: self.len() as u16,
-                        elements: self.into_iter().map(|e| e.try_into().unwrap()).collect()
-                    })
-                    
-                    //Err("garbage2".to_string())
-                }
-             
-         }
-       
          impl Dop2ParseTreeExpressible for #struct_name 
 {
-         fn from_parse_tree (payload: Dop2Payloads) -> Result<Self, String> { 
+         #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
+         fn from_parse_tree (payload: Dop2Payloads) -> Result<Self, Dop2Error> { 
          if let Dop2Payloads::MStruct(x)=payload 
          {
            // println!("{:?}", &x.fields.map(|x| s.field));
              
-            let y = Self {#(#constructor_fragments)*  }; 
+            #[allow(unused_mut)]
+            let mut y = Self {#(#constructor_fragments)*  };
+            #extra_read
             return Ok(y);
             //return Err("success".to_string());
             
@@ -238,7 +396,7 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
          else
          {
             //println!("{:?}", &payload);
-            return Err(format!("failed converting type: one or more field conversions failed in {}", stringify!(#struct_name)));
+            return Err(Dop2Error::type_mismatch(stringify!(#struct_name), Dop2PayloadsKind::MStruct, Dop2PayloadsKind::from(&payload)));
          }
         }
 }
@@ -247,3 +405,51 @@ let is_option = if let Type::Path(TypePath { path, .. }) = &field.ty {
 
     TokenStream::from(expanded)
 }
+
+/// Conversions between an enum and its `E8` or `E16` wire value, given as `#[dop2(wire = E16)]`.
+///
+/// Every variant carries its value, e.g. `AutoHotAir = 2`, apart from one
+/// `Unknown(u16)` that keeps any value without a name so it survives a round trip.
+#[proc_macro_derive(Dop2Enum, attributes(dop2))]
+pub fn derive_dop2_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    dop2enum::expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Whether the field is marked `#[dop2(extra)]`
+fn extra_attribute(field: &syn::Field) -> syn::Result<bool> {
+    let mut extra = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("dop2")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("extra") {
+                extra = true;
+                Ok(())
+            } else {
+                Err(meta.error("the only field option is `extra`"))
+            }
+        })?;
+    }
+    if extra {
+        if let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("dop2field")) {
+            return Err(syn::Error::new_spanned(attr, "a #[dop2(extra)] field has no #[dop2field]"));
+        }
+        let is_extra_fields = matches!(&field.ty, Type::Path(TypePath { path, .. }) if path.segments.last().is_some_and(|segment| segment.ident == "Dop2ExtraFields"));
+        if !is_extra_fields {
+            return Err(syn::Error::new_spanned(&field.ty, "a #[dop2(extra)] field is a `Dop2ExtraFields`"));
+        }
+    }
+    Ok(extra)
+}
+
+/// The struct type behind `Option<..>`, `Vec<..>` and `Box<..>` wrappers
+fn innermost_type(ty: &Type) -> &Type {
+    if let Type::Path(TypePath { path, .. }) = ty
+        && let Some(segment) = path.segments.last()
+        && ["Option", "Vec", "Box"].iter().any(|w| segment.ident == w)
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return innermost_type(inner);
+    }
+    ty
+}
@@ -0,0 +1,114 @@
+// Unit and attributes of a struct that is the root of a frame, and its registration
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Expr, ExprArray, ExprLit, ExprTuple, Ident, Lit, LitInt};
+
+/// Impls for `#[dop2(unit = 14, attribute = 130)]`, nothing for structs without it.
+/// A struct found at more than one unit lists each leaf, `#[dop2(leaves = [(1, 391), (2, 1585)])]`.
+/// `fields` are the field numbers of the struct and the Rust fields that hold them.
+///
+/// Everything is named by its full path, so the struct's module needs no imports for it.
+pub fn expand(input: &DeriveInput, fields: &[(u16, &Ident)]) -> syn::Result<TokenStream> {
+    let struct_name = &input.ident;
+    // (unit, attribute) of every leaf the struct is the root of, in declaration order
+    let mut leaves: Vec<(u16, u16)> = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("dop2")) {
+        let mut unit: Option<LitInt> = None;
+        let mut attributes: Vec<LitInt> = Vec::new();
+        let mut listed: Vec<(LitInt, LitInt)> = Vec::new();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("leaves") {
+                let expected = "expected `leaves = [(1, 391), (2, 1585)]`";
+                let Expr::Array(ExprArray { elems, .. }) = meta.value()?.parse::<Expr>()? else {
+                    return Err(meta.error(expected));
+                };
+                for elem in elems {
+                    match &elem {
+                        Expr::Tuple(ExprTuple { elems: pair, .. }) if pair.len() == 2 => match (&pair[0], &pair[1]) {
+                            (Expr::Lit(ExprLit { lit: Lit::Int(unit), .. }), Expr::Lit(ExprLit { lit: Lit::Int(attribute), .. })) => {
+                                listed.push((unit.clone(), attribute.clone()))
+                            }
+                            _ => return Err(syn::Error::new_spanned(elem, expected)),
+                        },
+                        _ => return Err(syn::Error::new_spanned(elem, expected)),
+                    }
+                }
+                Ok(())
+            } else if meta.path.is_ident("unit") {
+                unit = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("attribute") {
+                match meta.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => attributes.push(int),
+                    Expr::Array(ExprArray { elems, .. }) => {
+                        for elem in elems {
+                            match elem {
+                                Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => attributes.push(int),
+                                other => return Err(syn::Error::new_spanned(other, "attribute ids must be integer literals")),
+                            }
+                        }
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(other, "expected `attribute = 130` or `attribute = [131, 392]`"));
+                    }
+                }
+                Ok(())
+            } else {
+                Err(meta.error("expected `unit = ..`, `attribute = ..` or `leaves = ..`"))
+            }
+        })?;
+        if listed.is_empty() {
+            let unit = unit.ok_or_else(|| syn::Error::new_spanned(attr, "#[dop2(..)] needs the unit, e.g. `unit = 14`"))?;
+            if attributes.is_empty() {
+                return Err(syn::Error::new_spanned(attr, "#[dop2(..)] needs the attribute, e.g. `attribute = 130`"));
+            }
+            listed.extend(attributes.into_iter().map(|attribute| (unit.clone(), attribute)));
+        } else if unit.is_some() || !attributes.is_empty() {
+            return Err(syn::Error::new_spanned(attr, "`leaves` already names the unit and attribute of each leaf"));
+        }
+        for (unit, attribute) in listed {
+            let leaf = (unit.base10_parse::<u16>()?, attribute.base10_parse::<u16>()?);
+            if leaves.contains(&leaf) {
+                return Err(syn::Error::new_spanned(attribute, format!("{}/{} is already declared", leaf.0, leaf.1)));
+            }
+            leaves.push(leaf);
+        }
+    }
+    let Some((first_unit, first_attribute)) = leaves.first() else {
+        return Ok(quote!());
+    };
+    let (units, attributes): (Vec<u16>, Vec<u16>) = leaves.iter().copied().unzip();
+    let (numbers, idents): (Vec<u16>, Vec<&Ident>) = fields.iter().copied().unzip();
+
+    Ok(quote! {
+        impl #struct_name {
+            /// A frame carrying this value at its first unit and attribute
+            pub fn to_root_node(&self) -> ::core::result::Result<::dop2rs::payloader::root::RootNode, ::dop2rs::payloader::error::Dop2Error> {
+                ::core::result::Result::Ok(::dop2rs::payloader::root::RootNode::single(#first_unit, #first_attribute, self.to_dop2_struct_auto()?))
+            }
+        }
+
+        impl ::dop2rs::payloader::attribute_ids::HasAttributeIds for #struct_name {
+            const LEAVES: &'static [(u16, u16)] = &[ #( (#units, #attributes) ),* ];
+
+            fn field_debug(&self, index: u16) -> ::core::option::Option<::std::string::String> {
+                match index {
+                    #( #numbers => ::core::option::Option::Some(::std::format!("{:?}", self.#idents)), )*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn field_value(&self, index: u16) -> ::core::option::Option<&dyn ::core::any::Any> {
+                match index {
+                    #( #numbers => ::core::option::Option::Some(&self.#idents), )*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        ::dop2rs::inventory::submit! {
+            ::dop2rs::attribute_registry::AttributeRegistration::new::<#struct_name>(::core::stringify!(#struct_name))
+        }
+    })
+}
@@ -0,0 +1,19 @@
+// Converts leniently parsed frames into every type registered with `#[dop2(unit = .., attribute = ..)]`
+#![no_main]
+
+use dop2rs::attribute_registry::AttributeRegistry;
+use dop2rs::payloader::parser::Dop2Parser;
+use dop2rs::payloader::root::RootNode;
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+static REGISTRY: LazyLock<AttributeRegistry> = LazyLock::new(AttributeRegistry::new);
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(root_node) = RootNode::parse(&mut Dop2Parser::lenient(data)) else {
+        return;
+    };
+    for (unit, attribute_id) in REGISTRY.leaves() {
+        let _ = REGISTRY.decode(unit, attribute_id, root_node.root_struct.clone());
+    }
+});
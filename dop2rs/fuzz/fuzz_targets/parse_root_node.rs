@@ -0,0 +1,20 @@
+// Strict and lenient parsing of arbitrary bytes; whatever parses has to encode back to the bytes it came from
+#![no_main]
+
+use dop2rs::payloader::parser::Dop2Parser;
+use dop2rs::payloader::root::RootNode;
+use libfuzzer_sys::fuzz_target;
+
+fn round_trip(data: &[u8], mut parser: Dop2Parser) {
+    if let Ok(root_node) = RootNode::parse(&mut parser) {
+        let consumed = parser.position();
+        let mut bytes: Vec<u8> = Vec::new();
+        root_node.to_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, data[..consumed]);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    round_trip(data, Dop2Parser::new(data));
+    round_trip(data, Dop2Parser::lenient(data));
+});
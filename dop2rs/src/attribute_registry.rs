@@ -2,47 +2,152 @@
 // This uses a HashMap-based registry to map attribute IDs to handler functions
 
 use std::collections::HashMap;
-use crate::payloader::root::Dop2Struct;
+use crate::payloader::root::{Dop2FieldInfo, Dop2Fields, Dop2Struct};
+use crate::payloader::error::{Dop2Error, Dop2PathSegment};
+use crate::payloader::attribute_ids::HasAttributeIds;
+use crate::payloader::root::{Dop2Payloads, Dop2ParseTreeExpressible};
+use crate::payloader::diff::Dop2TypedChange;
 
-type AttributeHandler = fn(Dop2Struct) -> Result<(), String>;
+pub type AttributeHandler = fn(Dop2Struct) -> Result<Box<dyn std::fmt::Debug>, Dop2Error>;
+/// Decodes two readings and compares them field by field on the registered type
+pub type TypedDiffHandler = fn(Dop2Struct, Dop2Struct) -> Result<Vec<Dop2TypedChange>, Dop2Error>;
 
+/// A type declared with `#[dop2(unit = .., attribute = ..)]`, collected when the program is linked
+pub struct AttributeRegistration {
+    pub name: &'static str,
+    /// Unit and attribute of every leaf the type is the root of
+    pub leaves: &'static [(u16, u16)],
+    pub fields: &'static [Dop2FieldInfo],
+    pub decode: AttributeHandler,
+    pub diff: TypedDiffHandler,
+}
+
+impl AttributeRegistration {
+    pub const fn new<T>(name: &'static str) -> Self
+    where
+        T: Dop2ParseTreeExpressible + std::fmt::Debug + HasAttributeIds + Dop2Fields + 'static,
+    {
+        AttributeRegistration {
+            name,
+            leaves: T::LEAVES,
+            fields: T::DOP2_FIELDS,
+            decode: decode_boxed::<T>,
+            diff: diff_typed::<T>,
+        }
+    }
+}
+
+inventory::collect!(AttributeRegistration);
+
+fn decode_boxed<T>(struct_data: Dop2Struct) -> Result<Box<dyn std::fmt::Debug>, Dop2Error>
+where
+    T: Dop2ParseTreeExpressible + std::fmt::Debug + 'static,
+{
+    let decoded = T::from_parse_tree(Dop2Payloads::MStruct(struct_data))?;
+    Ok(Box::new(decoded) as Box<dyn std::fmt::Debug>)
+}
+
+/// One change per Rust field whose `Debug` differs, in the order the struct declares its fields
+fn diff_typed<T>(old: Dop2Struct, new: Dop2Struct) -> Result<Vec<Dop2TypedChange>, Dop2Error>
+where
+    T: Dop2ParseTreeExpressible + HasAttributeIds + Dop2Fields,
+{
+    let old = T::from_parse_tree(Dop2Payloads::MStruct(old))?;
+    let new = T::from_parse_tree(Dop2Payloads::MStruct(new))?;
+    Ok(T::DOP2_FIELDS
+        .iter()
+        .filter_map(|info| {
+            let (old, new) = (old.field_debug(info.index)?, new.field_debug(info.index)?);
+            (old != new).then_some(Dop2TypedChange { field: info.name, old, new })
+        })
+        .collect())
+}
+
+/// The registered types by unit and attribute; the same attribute number means different things in different units
 pub struct AttributeRegistry {
-    handlers: HashMap<u16, AttributeHandler>,
+    handlers: HashMap<(u16, u16), AttributeHandler>,
+    typed_diffs: HashMap<(u16, u16), TypedDiffHandler>,
+    schemas: HashMap<(u16, u16), (&'static str, &'static [Dop2FieldInfo])>,
+}
+
+impl Default for AttributeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AttributeRegistry {
     pub fn new() -> Self {
         let mut registry = AttributeRegistry {
             handlers: HashMap::new(),
+            typed_diffs: HashMap::new(),
+            schemas: HashMap::new(),
         };
         registry.register_all();
         registry
     }
 
-    pub fn handle(&self, attribute_id: u16, struct_data: Dop2Struct) -> Result<(), String> {
-        if let Some(handler) = self.handlers.get(&attribute_id) {
-            handler(struct_data)
-        } else {
-            Err(format!("No handler registered for attribute ID: {}", attribute_id))
+    pub fn handle(&self, unit: u16, attribute_id: u16, struct_data: Dop2Struct) -> Result<(), String> {
+        match self.decode(unit, attribute_id, struct_data) {
+            Some(Ok(decoded)) => {
+                println!("{decoded:#?}");
+                Ok(())
+            }
+            Some(Err(e)) => Err(e.to_string()),
+            None => Err(format!("No handler registered for {}/{}", unit, attribute_id)),
         }
     }
 
+    /// Converts the struct into the Rust type registered for the leaf, if there is one
+    pub fn decode(&self, unit: u16, attribute_id: u16, struct_data: Dop2Struct) -> Option<Result<Box<dyn std::fmt::Debug>, Dop2Error>> {
+        let handler = self.handlers.get(&(unit, attribute_id))?;
+        Some(handler(struct_data).map_err(|e| in_leaf(e, unit, attribute_id)))
+    }
+
+    /// Compares two readings of a leaf on its registered type, if there is one
+    pub fn typed_diff(&self, unit: u16, attribute_id: u16, old: Dop2Struct, new: Dop2Struct) -> Option<Result<Vec<Dop2TypedChange>, Dop2Error>> {
+        let handler = self.typed_diffs.get(&(unit, attribute_id))?;
+        Some(handler(old, new).map_err(|e| in_leaf(e, unit, attribute_id)))
+    }
+
+    /// Unit and attribute of every leaf with a registered type, in ascending order
+    pub fn leaves(&self) -> Vec<(u16, u16)> {
+        let mut leaves: Vec<(u16, u16)> = self.handlers.keys().copied().collect();
+        leaves.sort_unstable();
+        leaves
+    }
+
+    /// Name and field layout of the Rust type registered for a leaf
+    pub fn schema(&self, unit: u16, attribute_id: u16) -> Option<(&'static str, &'static [Dop2FieldInfo])> {
+        self.schemas.get(&(unit, attribute_id)).copied()
+    }
+
     // Public method to register a handler for a type
     pub fn register_handler<T>(&mut self) 
     where
-        T: crate::payloader::root::Dop2ParseTreeExpressible + std::fmt::Debug + crate::payloader::attribute_ids::HasAttributeIds,
+        T: Dop2ParseTreeExpressible + std::fmt::Debug + HasAttributeIds + Dop2Fields + 'static,
     {
-        for &attr_id in T::ATTRIBUTE_IDS {
-            self.handlers.insert(attr_id, |struct_data| {
-                use crate::payloader::root::{Dop2Payloads, Dop2ParseTreeExpressible};
-                let decoded = T::from_parse_tree(Dop2Payloads::MStruct(struct_data))?;
-                println!("{decoded:#?}");
-                Ok(())
-            });
+        let type_name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+        self.register(&AttributeRegistration::new::<T>(type_name));
+    }
+
+    fn register(&mut self, registration: &AttributeRegistration) {
+        for &leaf in registration.leaves {
+            self.schemas.insert(leaf, (registration.name, registration.fields));
+            self.handlers.insert(leaf, registration.decode);
+            self.typed_diffs.insert(leaf, registration.diff);
         }
     }
 
     fn register_all(&mut self) {
-        crate::payloader::attribute_ids::register_all_handlers(self);
+        for registration in inventory::iter::<AttributeRegistration> {
+            self.register(registration);
+        }
     }
 }
+
+fn in_leaf(error: Dop2Error, unit: u16, attribute_id: u16) -> Dop2Error {
+    error
+        .within(Dop2PathSegment::Attribute(attribute_id))
+        .within(Dop2PathSegment::Unit(unit))
+}
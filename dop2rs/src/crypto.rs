@@ -8,15 +8,20 @@ use aes::cipher::{block_padding::NoPadding,KeyInit};
 use sha2::Sha256;
 use hmac::{digest::Update, Hmac};
 
-struct GroupId([u8; 8]);
+use crate::payloader::root::{DopPadding, RootNode};
+use crate::Dop2Error;
+
+/// Identifies the key group an appliance was paired into
+#[derive(Clone, Copy)]
+pub struct GroupId(pub [u8; 8]);
 
 impl GroupId {
-    fn random () -> Self
+    pub fn random () -> Self
     {
         let mut rng = rand::rng();
         return GroupId { 0: rng.random()}
     }
-    fn from_hex (s: &str)->Self
+    pub fn from_hex (s: &str)->Self
     {
         GroupId {0: hex::decode(s).unwrap().try_into().unwrap()}
     }
@@ -30,7 +35,7 @@ impl fmt::Display for GroupId {
         Ok(())
     }
 }
-struct MieleKey([u8; 64]); // first 32 bytes are used as AES key. ALl 64 bytes are used for signature.
+pub struct MieleKey(pub [u8; 64]); // first 32 bytes are used as AES key. ALl 64 bytes are used for signature.
 
 impl MieleKey
 {
@@ -40,28 +45,28 @@ impl MieleKey
     }
 }
 
-struct MieleHmac ([u8; 32]);
+pub struct MieleHmac (pub [u8; 32]);
 
-pub struct AesIv ([u8; 16]);
+pub struct AesIv (pub [u8; 16]);
 impl AesIv {
-    fn random () -> Self
+    pub fn random () -> Self
     {
         let mut rng = rand::rng();
         return AesIv { 0: rng.random()}
     }
 }
-struct MieleSignature 
+pub struct MieleSignature 
 {
-    hmac : MieleHmac,
+    pub hmac : MieleHmac,
 }
 impl MieleSignature {
-    fn from_hex (s: &str)->MieleSignature
+    pub fn from_hex (s: &str)->MieleSignature
     {
         let b = hex::decode(s).unwrap();
         MieleSignature { hmac: MieleHmac{0: b[0..32].try_into().unwrap()} } 
     }
     
-    fn get_aes_iv (&self)->AesIv
+    pub fn get_aes_iv (&self)->AesIv
     {
         return AesIv{0: self.hmac.0[0..16].try_into().unwrap()}
     }
@@ -76,14 +81,15 @@ impl fmt::Display for MieleKey {
 }
 
 impl MieleKey {
-    fn random () -> Self
+    pub fn random () -> Self
     {
         let mut rng = rand::rng();
         return MieleKey { 0: rng.random()}
     }
 }
 
-struct MieleCryptoContext
+/// Group id and key shared with an appliance during pairing
+pub struct MieleCryptoContext
 {
     group_id : GroupId,
     group_key : MieleKey,
@@ -93,6 +99,8 @@ type Encryptor = cbc::Encryptor<aes::Aes256>;
 type Decryptor = cbc::Decryptor<aes::Aes256>;
 type Signer = Hmac<Sha256>;
 
+const AES_BLOCK_SIZE: usize = 16;
+
 pub struct MieleResponseSignatureInfo
 {
     pub status_code : u8,
@@ -113,7 +121,7 @@ pub struct MieleRequestSignatureInfo
 
 impl MieleResponseSignatureInfo
 {
-    fn to_bytes (&self) -> Vec<u8>
+    pub fn to_bytes (&self) -> Vec<u8>
     {
         let newline : u8  = b'\n';
         let mut bytes : Vec<u8> = Vec::new();
@@ -134,7 +142,7 @@ impl MieleResponseSignatureInfo
 }
 impl MieleRequestSignatureInfo
 {
-    fn to_bytes (&self) -> Vec<u8>
+    pub fn to_bytes (&self) -> Vec<u8>
     {
         let newline : u8  = b'\n';
         let mut bytes : Vec<u8> = Vec::new();
@@ -156,24 +164,23 @@ impl MieleRequestSignatureInfo
 
         bytes.extend_from_slice(self.date.as_bytes());
         bytes.push(newline);
-        
-       // bytes.extend_from_slice(&self.payload);   
-        //bytes.push(0);     
+
+        bytes.extend_from_slice(&self.payload);
 
         return bytes;   
     }
 }
 impl MieleCryptoContext 
 {
-    fn default() -> Self
+    pub fn new(group_id: GroupId, group_key: MieleKey) -> Self
     {
-        return MieleCryptoContext {group_id: GroupId{0: [0x31; 8]}, group_key: MieleKey{0: [0x11; 64]}};
+        MieleCryptoContext {group_id, group_key}
     }
-    fn random () -> Self
+    pub fn random () -> Self
     {
         return MieleCryptoContext {group_id: GroupId::random(), group_key: MieleKey::random()};
     }
-    fn signature (&self, buffer: &[u8])->[u8; 32]
+    pub fn signature (&self, buffer: &[u8])->[u8; 32]
     {
         let mut mac = Signer::new_from_slice(&self.group_key.0).unwrap();
         //mac.update(buffer);
@@ -182,16 +189,45 @@ impl MieleCryptoContext
         let signature = hmac::Mac::finalize(mac).into_bytes();
         return signature.into()
     }
-    fn decrypt (&self, buffer : Vec<u8>, iv : &AesIv)-> Vec<u8>
+    /// Signs a request with the group key, the body being the plaintext before encryption
+    pub fn sign_request (&self, request: &MieleRequestSignatureInfo) -> MieleHeader
+    {
+        let hmac = MieleHmac(self.signature(&request.to_bytes()));
+        MieleHeader {group_id: self.group_id, signature: MieleSignature {hmac}}
+    }
+    /// Pads the body, signs the request over the padded plaintext and encrypts the body
+    /// with the IV taken from that signature
+    pub fn seal_request (&self, mut request: MieleRequestSignatureInfo, body: MieleRequestBody) -> Result<MieleSealedRequest, MieleCryptoError>
     {
-       // println!("buffer len: {:?}", buffer.len());
-        let d: cbc::Decryptor<Aes256> = Decryptor::new_from_slices(&self.group_key.get_aes_key(), &iv.0).unwrap();
-        return d.decrypt_padded_vec_mut::<NoPadding>(buffer.as_slice())
-.unwrap()    }
-    fn encrypt (&self, buffer : Vec<u8>, iv : &AesIv)->Vec<u8>
+        request.payload = body.padded()?;
+        let header = self.sign_request(&request);
+        let body = self.encrypt(request.payload, &header.signature.get_aes_iv())?;
+        Ok(MieleSealedRequest {header, body})
+    }
+    /// The buffer must fill whole AES blocks, as anything the appliance encrypts does
+    pub fn decrypt (&self, buffer : Vec<u8>, iv : &AesIv)-> Result<Vec<u8>, MieleCryptoError>
+    {
+        if !buffer.len().is_multiple_of(AES_BLOCK_SIZE) {
+            return Err(MieleCryptoError::Unaligned(buffer.len()));
+        }
+        let d: cbc::Decryptor<Aes256> = Decryptor::new(&self.group_key.get_aes_key().into(), &iv.0.into());
+        d.decrypt_padded_vec_mut::<NoPadding>(buffer.as_slice()).map_err(|_| MieleCryptoError::Unaligned(buffer.len()))
+    }
+    /// The buffer must already fill whole AES blocks, see `MieleRequestBody::padded`
+    pub fn encrypt (&self, buffer : Vec<u8>, iv : &AesIv)->Result<Vec<u8>, MieleCryptoError>
+    {
+        if !buffer.len().is_multiple_of(AES_BLOCK_SIZE) {
+            return Err(MieleCryptoError::Unaligned(buffer.len()));
+        }
+        let e: cbc::Encryptor<Aes256> = Encryptor::new(&self.group_key.get_aes_key().into(), &iv.0.into());
+        Ok(e.encrypt_padded_vec_mut::<NoPadding>(buffer.as_slice()))
+    }
+}
+impl Default for MieleCryptoContext {
+    /// The group of a factory-reset appliance
+    fn default() -> Self
     {
-        let e: cbc::Encryptor<Aes256> = Encryptor::new_from_slices(&self.group_key.get_aes_key(), &iv.0).unwrap();
-        return e.encrypt_padded_vec_mut::<NoPadding>(buffer.as_slice());
+        MieleCryptoContext {group_id: GroupId{0: [0x31; 8]}, group_key: MieleKey{0: [0x11; 64]}}
     }
 }
 impl std::fmt::Display for MieleCryptoContext {
@@ -200,22 +236,116 @@ impl std::fmt::Display for MieleCryptoContext {
     }
 }
 
-struct MieleHeader{
-    group_id : GroupId,
-    signature : MieleSignature,
+pub struct MieleHeader{
+    pub group_id : GroupId,
+    pub signature : MieleSignature,
 }
 impl MieleHeader
 {
-    fn to_http_header(&self) -> String
+    /// `MieleH256 <group id>:<hmac>`, both in upper case hex like the app sends them
+    pub fn to_header_value(&self) -> String
+    {
+        format!("MieleH256 {}:{}", self.group_id.to_string().to_uppercase(), hex::encode_upper(self.signature.hmac.0))
+    }
+    pub fn to_http_header(&self) -> String
+    {
+        format!("Authorization: {}", self.to_header_value())
+    }
+    /// Accepts the whole header line, its value or just `<group id>:<hmac>`
+    pub fn from_http_header(s: String) -> Result<Self, MieleCryptoError>
+    {
+        let value = s.strip_prefix("Authorization:").map_or(s.as_str(), str::trim_start);
+        let value = value.strip_prefix("MieleH256 ").unwrap_or(value);
+        let parsed = value.split_once(':').and_then(|(group_id, hmac)| {
+            let group_id = hex::decode(group_id).ok()?.try_into().ok()?;
+            let hmac = hex::decode(hmac).ok()?.try_into().ok()?;
+            Some(MieleHeader {group_id: GroupId(group_id), signature: MieleSignature {hmac: MieleHmac(hmac)}})
+        });
+        parsed.ok_or(MieleCryptoError::MalformedHeader(s))
+    }
+}
+
+/// Failures while reading what a request is signed over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MieleCryptoError
+{
+    /// An `Authorization` header that is not `MieleH256 <group id>:<hmac>` in hex
+    MalformedHeader(String),
+    /// A part of the request that cannot be signed, like a header that is not text or a streamed body
+    Unsignable(&'static str),
+    /// A plaintext or ciphertext of this many bytes, which does not fill whole AES blocks
+    Unaligned(usize),
+    /// A JSON body that does not end in the `}` its padding goes in front of
+    UnclosedJson,
+}
+
+impl fmt::Display for MieleCryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MieleCryptoError::MalformedHeader(header) => write!(f, "malformed signature header {:?}", header),
+            MieleCryptoError::Unsignable(part) => write!(f, "cannot sign the {} of the request", part),
+            MieleCryptoError::Unaligned(length) => write!(f, "{} bytes do not fill whole AES blocks of {}", length, AES_BLOCK_SIZE),
+            MieleCryptoError::UnclosedJson => write!(f, "JSON body does not end in a closing brace"),
+        }
+    }
+}
+
+impl std::error::Error for MieleCryptoError {}
+
+/// A request body before it is padded and encrypted
+pub enum MieleRequestBody
+{
+    Json(String),
+    /// One or more DOP2 frames
+    Dop2(Vec<u8>),
+}
+impl MieleRequestBody
+{
+    /// The app never sends a JSON body shorter than this
+    const JSON_MINIMUM_LENGTH: usize = 64;
+
+    /// The plaintext that is signed and encrypted. JSON gets spaces before its closing brace,
+    /// up to 64 bytes and then to the 16-byte block size. DOP2 frames get 0x20 bytes up to the
+    /// next 16-byte boundary. An empty body stays empty.
+    pub fn padded(self) -> Result<Vec<u8>, MieleCryptoError>
     {
-        return "X-Signature: MieleH256 1111111111111111:DD361380F1AB6BC95C3A42144DA458CB58A204A4A509E14B59B7690D1846AAE3".to_string();
+        match self {
+            MieleRequestBody::Json(json) if json.is_empty() => Ok(Vec::new()),
+            MieleRequestBody::Json(json) => {
+                let length = json.len().max(Self::JSON_MINIMUM_LENGTH);
+                let length = length + usize::from(DopPadding::after(length).bytes_of_padding);
+                let open = json.strip_suffix('}').ok_or(MieleCryptoError::UnclosedJson)?;
+                let spaces = " ".repeat(length - json.len());
+                Ok(format!("{}{}}}", open, spaces).into_bytes())
+            }
+            MieleRequestBody::Dop2(mut frames) => {
+                let padding = DopPadding::after(frames.len()).bytes_of_padding;
+                frames.resize(frames.len() + usize::from(padding), b' ');
+                Ok(frames)
+            }
+        }
     }
-    fn from_http_header(s: String) -> Self
+}
+impl TryFrom<RootNode> for MieleRequestBody
+{
+    type Error = Dop2Error;
+
+    fn try_from(node: RootNode) -> Result<Self, Dop2Error>
     {
-        let parts : Vec<&str> = s.split(':').collect();
-        return MieleHeader {group_id: GroupId::from_hex(parts[0]), signature: MieleSignature::from_hex(parts[1])}
+        let mut frames = Vec::new();
+        node.to_bytes(&mut frames)?;
+        Ok(MieleRequestBody::Dop2(frames))
     }
 }
+
+/// The signature header and encrypted body of a request, ready to be sent
+pub struct MieleSealedRequest
+{
+    pub header: MieleHeader,
+    pub body: Vec<u8>,
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     
@@ -224,8 +354,8 @@ mod tests {
        let plaintext_string = "0123456789012345678901234567890123456789012345678901234567891234";
        let context : MieleCryptoContext = MieleCryptoContext::random(); 
        let iv = AesIv::random();
-       let ciphertext = context.encrypt(plaintext_string.as_bytes().into(), &iv);
-       let plaintext = context.decrypt(ciphertext, &iv);
+       let ciphertext = context.encrypt(plaintext_string.as_bytes().into(), &iv).unwrap();
+       let plaintext = context.decrypt(ciphertext, &iv).unwrap();
        //println!("{:}", context);
        assert_eq!(plaintext_string.as_bytes(), plaintext);
        
@@ -234,12 +364,12 @@ mod tests {
     fn test_decryption()
     {
         let test_key = hex::decode("123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE").unwrap();
-        let header = MieleHeader::from_http_header("123456789ABCDEFE:9258984775FF6450CF8D943A946C36C850FAAB65DEFCD2EAC6E7262B58308B47".to_string());
+        let header = MieleHeader::from_http_header("123456789ABCDEFE:9258984775FF6450CF8D943A946C36C850FAAB65DEFCD2EAC6E7262B58308B47".to_string()).unwrap();
         let context = MieleCryptoContext {group_id: GroupId::from_hex("123456789ABCDEFE"), group_key: MieleKey{0: test_key.try_into().unwrap()} } ;
         
 //        let ciphertext = hex::decode("f6eebe5e2bf7c5064c4d61c0da55c7e80010f700bd8b5d5c958e8165ab025bd5f65a002044ef3e573d2bfd1ee3eef862cb96115100307c472b5c7389793a6d713249b056231f0040e865b7931033e679f46c6a97ba6f58840050d58d0dc367e557f675d4092fb3254cb60060e9c0e4ca99b5c0a34df73a8802004cf90070b7fca41d0cbc521792df8ae4a0fc3e0e0080fefbc1d6550a7a66c13334680de6066c").unwrap();
         let ciphertext = hex::decode("8dc821a1c9eced3fa98fd74e0d6629b9ee41543376ea08dec33acca7949f6b1f812e2b828dae8c72f7ae0fa7670fa38a0ec8fe10e42988df0f09fa0815c2e2ee").unwrap();
-        let plaintext = context.decrypt(ciphertext, &header.signature.get_aes_iv());
+        let plaintext = context.decrypt(ciphertext, &header.signature.get_aes_iv()).unwrap();
       //  println!("{:?}", str::from_utf8(&plaintext).unwrap());
         assert_eq!(&plaintext, "{\"DeviceAction\": 2                                             }".to_string().as_bytes());
 
@@ -249,9 +379,9 @@ mod tests {
     fn test_response_decrypt_verify_sig ()
     {
         let context: MieleCryptoContext = MieleCryptoContext::default();
-        let header = MieleHeader::from_http_header("1111111111111111:DD361380F1AB6BC95C3A42144DA458CB58A204A4A509E14B59B7690D1846AAE3".to_string());
+        let header = MieleHeader::from_http_header("1111111111111111:DD361380F1AB6BC95C3A42144DA458CB58A204A4A509E14B59B7690D1846AAE3".to_string()).unwrap();
         let payload : Vec<u8> = hex::decode("f6eebe5e2bf7c5064c4d61c0da55c7e8f700bd8b5d5c958e8165ab025bd5f65a44ef3e573d2bfd1ee3eef862cb9611517c472b5c7389793a6d713249b056231fe865b7931033e679f46c6a97ba6f5884d58d0dc367e557f675d4092fb3254cb6e9c0e4ca99b5c0a34df73a8802004cf9b7fca41d0cbc521792df8ae4a0fc3e0efefbc1d6550a7a66c13334680de6066c").unwrap();
-        let plaintext = context.decrypt(payload, &header.signature.get_aes_iv());
+        let plaintext = context.decrypt(payload, &header.signature.get_aes_iv()).unwrap();
         let plaintext_str=str::from_utf8(&plaintext).unwrap();
       //  println!("{:?}", plaintext_str);
         assert!(plaintext_str.contains("ReleaseNotes"));
@@ -274,4 +404,67 @@ mod tests {
          let payload = header_fields.to_bytes();
          assert_eq!(hex::encode(context.signature(&payload)).to_uppercase(), "DBC5C3BD007CDDF0214645E4FF27F517AFA1025AA9E3C1030BB15AE2A4210D91");
     }
+    #[test]
+    fn test_signed_request_header() {
+        let test_key = hex::decode("123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE").unwrap();
+        let context = MieleCryptoContext {group_id: GroupId::from_hex("123456789abcdefe"), group_key: MieleKey(test_key.try_into().unwrap())};
+        let get = MieleRequestSignatureInfo {http_method: "GET".to_string(), host:"127.0.0.1".to_string(), request_uri: "/Devices/000177753917/DOP2/2/1585?idx1=0&idx2=1".to_string(), content_type: "application / vnd.miele.v1 + json; charset = utf - 8".to_string(), accept_header: "application/vnd.miele.v1+json".to_string(), date: "Thu, 01 Jan 1970 02:09:22 GMT".to_string(), payload: vec!()};
+        assert_eq!(context.sign_request(&get).to_http_header(), "Authorization: MieleH256 123456789ABCDEFE:DBC5C3BD007CDDF0214645E4FF27F517AFA1025AA9E3C1030BB15AE2A4210D91");
+
+        // the body is signed as well, vector from MieleCrypto.sign
+        let put = MieleRequestSignatureInfo {http_method: "PUT".to_string(), host:"127.0.0.1".to_string(), request_uri: "/Devices/000177753917/State".to_string(), content_type: "application/vnd.miele.v1+json; charset=utf-8".to_string(), accept_header: "application/vnd.miele.v1+json".to_string(), date: "Thu, 01 Jan 1970 02:09:22 GMT".to_string(), payload: b"{\"DeviceAction\": 2}".to_vec()};
+        let header = context.sign_request(&put);
+        assert_eq!(header.to_header_value(), "MieleH256 123456789ABCDEFE:A2B41F091ECCED8CD4EEAA73F6AE92D01B7E8D6619C714615917B435142B29AF");
+
+        let parsed = MieleHeader::from_http_header(header.to_http_header()).unwrap();
+        assert_eq!(parsed.group_id.0, header.group_id.0);
+        assert_eq!(parsed.signature.hmac.0, header.signature.hmac.0);
+
+        for malformed in ["Authorization: MieleH256 123456789ABCDEFE", "123456789ABCDEFE:A2B4", "zz:A2B41F091ECCED8CD4EEAA73F6AE92D01B7E8D6619C714615917B435142B29AF"] {
+            assert_eq!(MieleHeader::from_http_header(malformed.to_string()).err(), Some(MieleCryptoError::MalformedHeader(malformed.to_string())));
+        }
+    }
+    #[test]
+    fn test_padded_json_body_matches_app() {
+        let test_key = hex::decode("123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE").unwrap();
+        let header = MieleHeader::from_http_header("123456789ABCDEFE:9258984775FF6450CF8D943A946C36C850FAAB65DEFCD2EAC6E7262B58308B47".to_string()).unwrap();
+        let context = MieleCryptoContext {group_id: GroupId::from_hex("123456789ABCDEFE"), group_key: MieleKey(test_key.try_into().unwrap())};
+
+        let padded = MieleRequestBody::Json("{\"DeviceAction\": 2}".to_string()).padded().unwrap();
+        assert_eq!(&padded, "{\"DeviceAction\": 2                                             }".as_bytes());
+        let ciphertext = context.encrypt(padded, &header.signature.get_aes_iv()).unwrap();
+        assert_eq!(hex::encode(ciphertext), "8dc821a1c9eced3fa98fd74e0d6629b9ee41543376ea08dec33acca7949f6b1f812e2b828dae8c72f7ae0fa7670fa38a0ec8fe10e42988df0f09fa0815c2e2ee");
+
+        // longer bodies still end on a block boundary
+        let long = format!("{{\"ProgramName\": \"{}\"}}", "x".repeat(60));
+        let padded = MieleRequestBody::Json(long).padded().unwrap();
+        assert_eq!(padded.len(), 80);
+        assert!(padded.ends_with(b" }"));
+        assert!(MieleRequestBody::Json(String::new()).padded().unwrap().is_empty());
+        assert_eq!(MieleRequestBody::Json("{\"DeviceAction\": 2".to_string()).padded(), Err(MieleCryptoError::UnclosedJson));
+    }
+    #[test]
+    fn test_sealed_dop2_body_decrypts_with_signature_iv() {
+        use crate::payloader::root::{Dop2Payloads, Dop2Struct};
+        use crate::payloader::prelude::{Dop2PayloadsKind, TaggedDopField};
+
+        let context = MieleCryptoContext::default();
+        let user_request = Dop2Struct::from_fields(vec![TaggedDopField {field_index: 1, tag: Dop2PayloadsKind::E16, value: Dop2Payloads::E16(2.into())}]).unwrap();
+        let body = MieleRequestBody::try_from(RootNode::single(2, 1583, user_request)).unwrap();
+        let request = MieleRequestSignatureInfo {http_method: "PUT".to_string(), host:"127.0.0.1".to_string(), request_uri: "/Devices/000177753917/DOP2/2/1583?idx1=0&idx2=0".to_string(), content_type: "application/vnd.miele.dop2".to_string(), accept_header: "application/vnd.miele.v1+json".to_string(), date: "Thu, 01 Jan 1970 02:09:22 GMT".to_string(), payload: vec!()};
+        let sealed = context.seal_request(request, body).unwrap();
+        let ciphertext = sealed.body.clone();
+        assert_eq!(sealed.body.len() % 16, 0);
+
+        let plaintext = context.decrypt(sealed.body, &sealed.header.signature.get_aes_iv()).unwrap();
+        assert_eq!(hex::encode(&plaintext), "000f0002062f0000000000010001070002202020202020202020202020202020");
+        let signed = MieleRequestSignatureInfo {http_method: "PUT".to_string(), host:"127.0.0.1".to_string(), request_uri: "/Devices/000177753917/DOP2/2/1583?idx1=0&idx2=0".to_string(), content_type: "application/vnd.miele.dop2".to_string(), accept_header: "application/vnd.miele.v1+json".to_string(), date: "Thu, 01 Jan 1970 02:09:22 GMT".to_string(), payload: plaintext};
+        assert_eq!(context.signature(&signed.to_bytes()), sealed.header.signature.hmac.0);
+
+        // unaligned input is rejected rather than padded
+        let iv = AesIv::random();
+        assert_eq!(context.encrypt(b"{}".to_vec(), &iv).err(), Some(MieleCryptoError::Unaligned(2)));
+        assert_eq!(context.encrypt(b"{}".to_vec(), &iv).unwrap_err().to_string(), "2 bytes do not fill whole AES blocks of 16");
+        assert_eq!(context.decrypt(ciphertext[..31].to_vec(), &iv), Err(MieleCryptoError::Unaligned(31)));
+    }
 }
@@ -1,50 +1,150 @@
-use reqwest::{Request, Response};
-use reqwest_middleware::{Middleware, Next, Result};
+use std::sync::Arc;
+
+use reqwest::{Body, Request, Response};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest_middleware::{Error, Middleware, Next, Result};
 use http::Extensions;
 
-use crate::crypto::MieleRequestSignatureInfo;
+use crate::crypto::{MieleCryptoContext, MieleCryptoError, MieleRequestBody, MieleRequestSignatureInfo};
 
+/// Middleware of the appliance client, which signs every request with the group key and
+/// seals its body, if it has one; the signature goes into the `Authorization` header
 #[derive(Clone)]
-struct MieleAuthMiddleware
+pub struct MieleAuthMiddleware
+{
+    context: Arc<MieleCryptoContext>,
+}
+
+impl MieleAuthMiddleware
 {
-    
+    pub fn new(context: MieleCryptoContext) -> Self
+    {
+        MieleAuthMiddleware {context: Arc::new(context)}
+    }
 }
 
 impl MieleRequestSignatureInfo
 {
-    fn get_header_str<'a> (request: &'a Request, key: &'a str)-> &'a str
+    /// An absent header is signed as empty
+    fn get_header_str<'a> (request: &'a Request, key: &'static str)-> std::result::Result<&'a str, MieleCryptoError>
     {
-         request.headers().get(key).map_or("", |x| x.to_str().unwrap())
+        request.headers().get(key).map_or(Ok(""), |x| x.to_str().map_err(|_| MieleCryptoError::Unsignable(key)))
     }
-    fn from_request (request: &Request) -> crate::crypto::MieleRequestSignatureInfo
+    /// What a request is signed over, its body taken as the plaintext; the host is the one
+    /// of the URL, with the port when it is not the default one
+    pub fn from_request (request: &Request) -> std::result::Result<Self, MieleCryptoError>
     {
         let http_method = request.method().as_str();
-        let host = Self::get_header_str(request, "Host");
-        let request_uri = request.url().as_str();
-        let date = Self::get_header_str(request, "Date");
-        let content_type =  Self::get_header_str(request, "Content-Type");
-        let accept_header =  Self::get_header_str(request, "Accept");
-        crate::crypto::MieleRequestSignatureInfo {accept_header: accept_header.to_string(), content_type: content_type.to_string(), date: date.to_string(), host: host.to_string(), http_method: http_method.to_string(), request_uri: request_uri.to_string(), payload: vec!()}
-    }
- /*   fn to_request () -> Request 
-    {
-
-    }*/
+        let url = request.url();
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(MieleCryptoError::Unsignable("host")),
+        };
+        let request_uri = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let date = Self::get_header_str(request, "Date")?;
+        let content_type =  Self::get_header_str(request, "Content-Type")?;
+        let accept_header =  Self::get_header_str(request, "Accept")?;
+        let payload = match request.body() {
+            Some(body) => body.as_bytes().ok_or(MieleCryptoError::Unsignable("streamed body"))?.to_vec(),
+            None => Vec::new(),
+        };
+        Ok(MieleRequestSignatureInfo {accept_header: accept_header.to_string(), content_type: content_type.to_string(), date: date.to_string(), host, http_method: http_method.to_string(), request_uri, payload})
+    }
 }
 
 #[async_trait::async_trait]
 impl Middleware for MieleAuthMiddleware {
     async fn handle(
         &self,
-        req: Request,
+        mut req: Request,
         extensions: &mut Extensions,
         next: Next<'_>,
     ) -> Result<Response> {
-        println!("Request started {:?}", req);
-        let res = next.run(req, extensions).await;
-        println!("Result: {:?}", res);
-        res
+        let mut info = MieleRequestSignatureInfo::from_request(&req).map_err(Error::middleware)?;
+        let header = if info.payload.is_empty() {
+            self.context.sign_request(&info)
+        } else {
+            // DOP2 frames are sent as application/vnd.miele.dop2, anything else is JSON
+            let payload = std::mem::take(&mut info.payload);
+            let body = if info.content_type.contains("dop2") {
+                MieleRequestBody::Dop2(payload)
+            } else {
+                MieleRequestBody::Json(String::from_utf8(payload).map_err(|_| Error::middleware(MieleCryptoError::Unsignable("body")))?)
+            };
+            let sealed = self.context.seal_request(info, body).map_err(Error::middleware)?;
+            *req.body_mut() = Some(Body::from(sealed.body));
+            sealed.header
+        };
+        let value = HeaderValue::from_str(&header.to_header_value()).map_err(Error::middleware)?;
+        req.headers_mut().insert(AUTHORIZATION, value);
+        next.run(req, extensions).await
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::Method;
+
+    use super::*;
+    use crate::crypto::{GroupId, MieleKey, MieleRequestBody};
 
+    /// What reached the network side of the signing middleware
+    struct Sent {
+        method: Method,
+        authorization: Option<HeaderValue>,
+        body: Vec<u8>,
+    }
+
+    /// Stands in for the network, keeping the requests the signing middleware passes on
+    #[derive(Clone, Default)]
+    struct Recorder {
+        requests: Arc<Mutex<Vec<Sent>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Middleware for Recorder {
+        async fn handle(&self, req: Request, _extensions: &mut Extensions, _next: Next<'_>) -> Result<Response> {
+            let body = req.body().and_then(Body::as_bytes).unwrap_or_default().to_vec();
+            self.requests.lock().unwrap().push(Sent {method: req.method().clone(), authorization: req.headers().get(AUTHORIZATION).cloned(), body});
+            Ok(Response::from(http::Response::new("")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_signed_in_the_authorization_header() {
+        let test_key = hex::decode("123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE123456789ABCDEFE").unwrap();
+        let group_key: [u8; 64] = test_key.try_into().unwrap();
+        let context = || MieleCryptoContext::new(GroupId::from_hex("123456789abcdefe"), MieleKey(group_key));
+        let recorder = Recorder::default();
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(MieleAuthMiddleware::new(context()))
+            .with(recorder.clone())
+            .build();
+
+        let url = "http://127.0.0.1/Devices/000177753917/DOP2/2/1585?idx1=0&idx2=1";
+        let date = "Thu, 01 Jan 1970 02:09:22 GMT";
+        client.get(url).header("Accept", "application/vnd.miele.v1+json").header("Date", date).send().await.unwrap();
+        let json = "application/vnd.miele.v1+json; charset=utf-8";
+        client.put("http://127.0.0.1/Devices/000177753917/State").header("Content-Type", json).header("Accept", "application/vnd.miele.v1+json").header("Date", date)
+            .body("{\"DeviceAction\": 2}").send().await.unwrap();
+
+        let get = MieleRequestSignatureInfo {http_method: "GET".to_string(), host: "127.0.0.1".to_string(), request_uri: "/Devices/000177753917/DOP2/2/1585?idx1=0&idx2=1".to_string(), content_type: String::new(), accept_header: "application/vnd.miele.v1+json".to_string(), date: date.to_string(), payload: vec!()};
+        let put = MieleRequestSignatureInfo {http_method: "PUT".to_string(), host: "127.0.0.1".to_string(), request_uri: "/Devices/000177753917/State".to_string(), content_type: json.to_string(), accept_header: "application/vnd.miele.v1+json".to_string(), date: date.to_string(), payload: vec!()};
+        let sealed = context().seal_request(put, MieleRequestBody::Json("{\"DeviceAction\": 2}".to_string())).unwrap();
+
+        let requests = recorder.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].authorization.as_ref().unwrap(), context().sign_request(&get).to_header_value().as_str());
+        assert!(requests[0].body.is_empty());
+        assert_eq!(requests[1].method, Method::PUT);
+        assert_eq!(requests[1].authorization.as_ref().unwrap(), sealed.header.to_header_value().as_str());
+        assert_eq!(requests[1].body, sealed.body);
+    }
+}
@@ -0,0 +1,38 @@
+//! Typed DOP2 payloads for Miele appliances on top of the `dop2core` wire format.
+//!
+//! - [`payloader`] holds the typed payloads per attribute, the frame codec, dumps and diffs
+//! - [`attribute_registry`] decodes a frame into the type registered for its attribute
+//! - [`crypto`] signs and encrypts requests (feature `crypto`)
+//! - [`device_api`] is the reqwest middleware for talking to an appliance (feature `client`)
+//!
+//! Payloads only found on one appliance family sit behind the `oven`, `washer`
+//! and `coffee` features. Timestamps are `chrono` types with the `chrono` feature
+//! and seconds since the epoch without it. The `dop2rs` binary needs the `cli` feature.
+
+// `#[dop2(unit = .., attribute = ..)]` names everything it generates by `::dop2rs::` paths
+extern crate self as dop2rs;
+#[doc(hidden)]
+pub use inventory;
+
+#[cfg(feature = "crypto")]
+pub mod crypto;
+#[cfg(feature = "client")]
+pub mod device_api;
+pub mod attribute_registry;
+pub use payloader::helper::types::*;
+
+// Re-export Dop types for macro usage
+pub use payloader::root::{Dop2Payloads, Dop2PayloadsKind, Dop2Struct, TaggedDopField, Dop2ExtraFields, Dop2ParseTreeExpressible, RootNode};
+pub use payloader::parser::{DopArray, ToDop2Bytes, Dop2PayloadExpressible, Dop2Parser, Dop2Limits};
+pub use payloader::error::{Dop2Error, Dop2Limit, Dop2Path, Dop2PathSegment};
+pub use payloader::codec::Dop2Codec;
+pub use attribute_registry::AttributeRegistry;
+
+pub mod payloader;
+#[macro_use]
+pub mod macros;
+
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod tests;
@@ -1,25 +1,34 @@
 
 use clap::Parser;
 
-use payloader::unit_ids::UnitIds;
+use dop2rs::payloader;
+use dop2rs::attribute_registry;
 use payloader::device::generic::program_selection::enums::{ProgramIdOven, SelectionType};
 use payloader::device::generic::request::UserRequestOven;
 use payloader::root::RootNode;
-
-
-mod crypto;
-mod device_api;
-mod attribute_registry;
-pub use payloader::helper::types::*;
-
-// Re-export Dop types for macro usage
-pub use payloader::root::{Dop2Payloads, Dop2PayloadsKind, Dop2Struct, TaggedDopField, Dop2ParseTreeExpressible};
-pub use payloader::parser::{DopArray, ToDop2Bytes, Dop2PayloadExpressible, Dop2Parser};
+use payloader::codec::Dop2Codec;
 
 #[derive(Parser, Debug)]
 struct Args {
-    /// The hex string to parse
+    /// The hex string to parse, a command, or a frame in text notation such as
+    /// "2/1577 { 1: E16(13), 2: U16(0), 3: E8(1) }" to encode
     hex_string: Option<String>,
+
+    /// Print an annotated hex dump of the frames instead of decoding them
+    #[arg(long)]
+    dump: bool,
+
+    /// A later reading of the same attribute to compare the hex string against
+    #[arg(long, value_name = "NEW_HEX")]
+    diff: Option<String>,
+
+    /// Print the diff as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Print decoded frames as Rust debug output instead of text notation
+    #[arg(long)]
+    debug: bool,
     
     // Unit parameter (optional)
    // #[arg(short, long)]
@@ -30,20 +39,15 @@ struct Args {
    // attribute: Option<u16>,
 }
 
-mod payloader;
-#[macro_use]
-pub mod macros;
-
-
-use crate::payloader::comm_module::request::request::{XkmRequestId, XkmRequest};
+use payloader::comm_module::request::request::{XkmRequestId, XkmRequest};
 use strum::IntoEnumIterator;
 use std::str::FromStr;
 
 fn main() {
     let args = Args::parse();
 
-    let command_verbs_xkm = payloader::comm_module::request::request::XkmRequestId::iter().map(|x| x.to_string());
-    let command_verbs_program = ProgramIdOven::iter().map(|x| x.to_string());
+    let command_verbs_xkm = payloader::comm_module::request::request::XkmRequestId::iter().filter(|x| !x.is_unknown()).map(|x| x.to_string());
+    let command_verbs_program = ProgramIdOven::iter().filter(|x| !x.is_unknown()).map(|x| x.to_string());
    // let command_verbs_user_request = UserRequestOven::iter().map(|x| x.to_string());
     //let mut it : Vec<String> = command_verbs_xkm.chain(command_verbs_program)
     //.chain(command_verbs_user_request)
@@ -58,12 +62,10 @@ fn main() {
 
         eprintln!("Sending XKM command {:?}", xkm);
         let request = XkmRequest{request_id: xkm};
-        let payload = request.to_dop2_struct_auto().unwrap();
-
-        let root = RootNode::single(UnitIds::CommunicationsModule.into(), XkmRequest::ATTRIBUTE_IDS.first().unwrap().clone(), payload);
+        let root = request.to_root_node().unwrap();
        
         let mut data : Vec<u8> = vec!();
-        root.to_bytes(&mut data);
+        root.to_bytes(&mut data).unwrap();
        // payload.to_bytes(&mut data);
         let hexdump = hex::encode(data);
         println!("{}", hexdump);
@@ -73,12 +75,10 @@ fn main() {
     {
         eprintln!("Sending PS command {:?}", program_id);
         let request : payloader::device::generic::program_selection::select::PsSelect = payloader::device::generic::program_selection::select::PsSelect { program_id, selection_parameter: 0, selection_type: SelectionType::InitialDefault };
-        let payload = request.to_dop2_struct_auto().unwrap();
-
-        let root = RootNode::single(UnitIds::MainDevice.into(), payloader::device::generic::program_selection::select::PsSelect::ATTRIBUTE_IDS.first().unwrap().clone(), payload);
+        let root = request.to_root_node().unwrap();
        
         let mut data : Vec<u8> = vec!();
-        root.to_bytes(&mut data);
+        root.to_bytes(&mut data).unwrap();
        // payload.to_bytes(&mut data);
         let hexdump = hex::encode(data);
         println!("{}", hexdump);
@@ -93,6 +93,15 @@ fn main() {
             parameter1: None,
         };
     }
+    else if let Ok(root) = RootNode::from_str(command)
+    {
+        let mut data : Vec<u8> = vec!();
+        if let Err(e) = root.to_bytes(&mut data) {
+            eprintln!("Error encoding frame: {}", e);
+            std::process::exit(1);
+        }
+        println!("{}", hex::encode(data));
+    }
     else {
         let hex_str = match &args.hex_string {
             Some(s) => s,
@@ -115,19 +124,60 @@ fn main() {
             std::process::exit(1);
         }
     };
-    let mut parser = Dop2Parser::new(bytes);
-    let root_node = RootNode::parse(&mut parser).unwrap();
-    println!("{root_node:#?}");
-    
-    // Use Registry Pattern to handle attribute decoding
     let registry = attribute_registry::AttributeRegistry::new();
-    if let Err(e) = registry.handle(root_node.attribute, root_node.root_struct) {
-        eprintln!("Warning: {}", e);
+    if args.dump {
+        print!("{}", payloader::dump::annotated_dump(&bytes, &registry));
+        return;
+    }
+    if let Some(new_hex) = &args.diff {
+        let decode = |bytes: &[u8]| match Dop2Codec::new().decode_body(bytes).map(|frames| frames.into_iter().next()) {
+            Ok(Some(root_node)) => root_node,
+            Ok(None) => {
+                eprintln!("Error decoding frame: no frame");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error decoding frame: {}", e);
+                std::process::exit(1);
+            }
+        };
+        let new_bytes = hex::decode(new_hex).unwrap_or_else(|e| {
+            eprintln!("Error decoding hex string: {}", e);
+            std::process::exit(1);
+        });
+        let diff = payloader::diff::Dop2Diff::new(&decode(&bytes), &decode(&new_bytes), &registry).unwrap_or_else(|e| {
+            eprintln!("Error diffing frames: {}", e);
+            std::process::exit(1);
+        });
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+        } else {
+            print!("{}", diff);
+        }
+        return;
+    }
+
+    let frames = match Dop2Codec::new().decode_body(&bytes) {
+        Ok(frames) => frames,
+        Err(e) => {
+            eprintln!("Error decoding frame: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Use Registry Pattern to handle attribute decoding
+    for root_node in frames {
+        if args.debug {
+            println!("{root_node:#?}");
+        } else {
+            println!("{root_node:#}");
+        }
+        if let Err(e) = registry.handle(root_node.unit, root_node.attribute, root_node.root_struct) {
+            eprintln!("Warning: {}", e);
+        }
     }
 
     }       
     
 }
 
-#[cfg(test)]
-mod tests;
@@ -0,0 +1,191 @@
+// Framing of DOP2 frames in a byte stream
+
+use crate::payloader::error::Dop2Error;
+use crate::payloader::parser::{Dop2Limits, Dop2Parser};
+use crate::payloader::root::{DopPadding, RootNode};
+use bytes::{BufMut, BytesMut};
+use std::fmt;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Splits a byte stream into `RootNode` frames and writes them back out.
+///
+/// A frame is its u16 length word, the `declared_length` bytes it announces and
+/// the 0x20 padding up to the next 16-byte boundary, so the boundary of every
+/// frame is known as soon as its first two bytes have arrived. A response body
+/// holding several concatenated frames decodes into one `RootNode` per frame.
+///
+/// Every frame is parsed under the codec's `Dop2Limits`.
+#[derive(Debug, Clone)]
+pub struct Dop2Codec {
+    limits: Dop2Limits,
+}
+
+impl Dop2Codec {
+    /// The largest frame a u16 length word can describe, padding included
+    pub const MAX_FRAME_SIZE: usize = Dop2Limits::MAX_FRAME_SIZE;
+
+    pub fn new() -> Self {
+        Self::with_limits(Dop2Limits::default())
+    }
+
+    pub fn with_limits(limits: Dop2Limits) -> Self {
+        Dop2Codec { limits }
+    }
+
+    /// Rejects frames longer than `max_frame_size` bytes before buffering them
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+        Self::with_limits(Dop2Limits {
+            max_frame_size,
+            ..Dop2Limits::default()
+        })
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.limits.max_frame_size
+    }
+
+    pub fn limits(&self) -> &Dop2Limits {
+        &self.limits
+    }
+
+    /// Decodes a complete body, e.g. an HTTP response, that holds one or more frames
+    pub fn decode_body(&mut self, body: &[u8]) -> Result<Vec<RootNode>, Dop2CodecError> {
+        let mut buffer = BytesMut::from(body);
+        let mut frames = Vec::new();
+        while let Some(frame) = self.decode_eof(&mut buffer)? {
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// Length of the frame at the start of `src`, padding included
+    fn frame_length(&self, src: &BytesMut) -> Result<Option<(usize, usize)>, Dop2CodecError> {
+        if src.len() < 2 {
+            return Ok(None);
+        }
+        let declared_length = u16::from_be_bytes([src[0], src[1]]);
+        let content_length = 2 + usize::from(declared_length);
+        let frame_length = content_length + usize::from(DopPadding::after(content_length).bytes_of_padding);
+        if frame_length > self.max_frame_size() {
+            return Err(Dop2CodecError::FrameTooLarge {
+                length: frame_length,
+                max: self.max_frame_size(),
+            });
+        }
+        Ok(Some((content_length, frame_length)))
+    }
+
+    fn parse_frame(&self, frame: &[u8]) -> Result<RootNode, Dop2CodecError> {
+        let mut parser = Dop2Parser::with_limits(frame, self.limits);
+        Ok(RootNode::parse(&mut parser)?)
+    }
+}
+
+impl Default for Dop2Codec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for Dop2Codec {
+    type Item = RootNode;
+    type Error = Dop2CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<RootNode>, Dop2CodecError> {
+        let Some((_, frame_length)) = self.frame_length(src)? else {
+            return Ok(None);
+        };
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+        let frame = src.split_to(frame_length);
+        self.parse_frame(&frame).map(Some)
+    }
+
+    /// At the end of the stream the padding of the last frame may be missing
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<RootNode>, Dop2CodecError> {
+        if let Some(frame) = self.decode(src)? {
+            return Ok(Some(frame));
+        }
+        if src.is_empty() {
+            return Ok(None);
+        }
+        match self.frame_length(src)? {
+            Some((content_length, _)) if src.len() >= content_length => {
+                let frame = src.split_to(src.len());
+                self.parse_frame(&frame).map(Some)
+            }
+            _ => Err(Dop2CodecError::TruncatedFrame {
+                available: src.len(),
+            }),
+        }
+    }
+}
+
+impl Encoder<RootNode> for Dop2Codec {
+    type Error = Dop2CodecError;
+
+    fn encode(&mut self, item: RootNode, dst: &mut BytesMut) -> Result<(), Dop2CodecError> {
+        let mut frame: Vec<u8> = Vec::new();
+        item.to_bytes(&mut frame)?;
+        if frame.len() > self.max_frame_size() {
+            return Err(Dop2CodecError::FrameTooLarge {
+                length: frame.len(),
+                max: self.max_frame_size(),
+            });
+        }
+        dst.put_slice(&frame);
+        Ok(())
+    }
+}
+
+/// Failures while framing a byte stream
+#[derive(Debug)]
+pub enum Dop2CodecError {
+    /// The underlying transport failed
+    Io(std::io::Error),
+    /// A complete frame was cut out of the stream but did not parse, or a frame to send did not encode
+    Frame(Dop2Error),
+    /// The length word announces a frame larger than the configured maximum
+    FrameTooLarge { length: usize, max: usize },
+    /// The stream ended in the middle of a frame
+    TruncatedFrame { available: usize },
+}
+
+impl fmt::Display for Dop2CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dop2CodecError::Io(e) => write!(f, "i/o error: {}", e),
+            Dop2CodecError::Frame(e) => write!(f, "{}", e),
+            Dop2CodecError::FrameTooLarge { length, max } => {
+                write!(f, "frame of {} bytes exceeds the maximum of {}", length, max)
+            }
+            Dop2CodecError::TruncatedFrame { available } => {
+                write!(f, "stream ended with {} bytes of an incomplete frame", available)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Dop2CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Dop2CodecError::Io(e) => Some(e),
+            Dop2CodecError::Frame(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Dop2CodecError {
+    fn from(e: std::io::Error) -> Self {
+        Dop2CodecError::Io(e)
+    }
+}
+
+impl From<Dop2Error> for Dop2CodecError {
+    fn from(e: Dop2Error) -> Self {
+        Dop2CodecError::Frame(e)
+    }
+}
@@ -2,19 +2,21 @@ use crate::payloader::prelude::*;
 use crate::payloader::helper::types::Dop2IpV4Adress;
 use crate::Dop2ParseTreeExpressible;
 
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum WifiSecurityProtocol {
     Open = 0,
     WEP = 1,
     WPA = 2,
     WPA2 = 3,
     WPA3 = 4,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(WifiSecurityProtocol, E8);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1573)]
 pub struct XkmConfigIp {
     #[dop2field(1, Dop2Payloads::Boolean)]
     ip_auto: bool,
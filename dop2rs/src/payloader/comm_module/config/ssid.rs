@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::comm_module::config::ip::WifiSecurityProtocol;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 110)]
 pub struct XkmConfigSsidList {
     #[dop2field(1, Dop2Payloads::ArrayU8)]
     ssid: DopArray<u8>, // WiFi SSID (32 bytes, null-terminated string)
@@ -13,6 +13,7 @@ use crate::Dop2ParseTreeExpressible;
 /// - applicationScope
 /// - macAddressLan
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1565)]
 pub struct XkmIdent {
     /// Application type (enum in Python, stored as E8 here)
     #[dop2field(2, Dop2Payloads::E8)]
@@ -9,6 +9,7 @@ use crate::Dop2ParseTreeExpressible;
 /// - technicalType
 /// - materialNumber
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1566)]
 pub struct XkmIdentLabel {
     // Serial number as a fixed-length ASCII byte array
     #[dop2field(1, Dop2Payloads::ArrayU8)]
@@ -1,8 +1,9 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum XkmRequestId {
     NoRequest = 0,
     Reset = 1,
@@ -12,19 +13,19 @@ pub enum XkmRequestId {
     OpenSoftAccessPointCustomerService = 45,
     ShutdownXkm = 46,
     MieleSmartConnect = 47,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(XkmRequestId, E8);
-
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 130)]
 pub struct XkmRequest {
     #[dop2field(1, Dop2Payloads::E8)]
-        pub(crate) request_id : XkmRequestId
+        pub request_id : XkmRequestId
 }
     impl XkmRequest
     {
-        pub const ATTRIBUTE : u16 = 130; // typically unit 14
 /*        pub fn from_parse_tree (payload: Dop2Payloads) -> Result<Self, String>
         {
             if let Dop2Payloads::MStruct(x)=payload // if payload cannot be deserialized as struct, fail
@@ -1,11 +1,12 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
-use crate::payloader::helper::types::Dop2TimestampUtc;
+use crate::payloader::helper::types::Dop2Timestamp;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 122)]
 pub struct DateTimeInfo {
     #[dop2field(1, Dop2Payloads::U64)]
-    utc_time: Dop2TimestampUtc,
+    utc_time: Dop2Timestamp,
     #[dop2field(2, Dop2Payloads::I32)]
     utc_offset: i32,
 }
@@ -4,18 +4,18 @@ use crate::Dop2ParseTreeExpressible;
 /// Enum representing the XKM connection state (mirrors `EnumXKMState` in the
 /// original implementation, but without the `GLOBAL_` prefix in the name
 /// or the variants).
-#[repr(u8)]
+#[repr(u16)]
 #[derive(
     Debug,
     Clone,
-    TryFromPrimitive,
-    IntoPrimitive,
     PartialEq,
     Eq,
     EnumIter,
     EnumString,
     strum_macros::Display,
+    Dop2Enum,
 )]
+#[dop2(wire = E8)]
 pub enum XkmState {
     Init = 0,
     NotConnected = 1,
@@ -25,11 +25,12 @@ pub enum XkmState {
     NetworkDeactivated = 7,
     Connecting = 8,
     ConnectedEthernet = 9,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(XkmState, E8);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1568)]
 pub struct XkmStateInfo {
     #[dop2field(1, Dop2Payloads::E8)]
     state: XkmState,
@@ -75,6 +76,9 @@ pub struct XkmStateInfo {
 
     #[dop2field(15, Dop2Payloads::E8)]
     bluetooth_state: E8,
+
+    #[dop2(extra)]
+    extra: Dop2ExtraFields,
 }
 
 impl_tryfrom_dop2struct!(XkmStateInfo);
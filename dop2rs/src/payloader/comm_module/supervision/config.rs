@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::device::generic::enums::MieleDeviceId;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1570)]
 pub struct SuperVisionListConfig {
         #[dop2field(1, Dop2Payloads::Boolean)]
         active : bool,
@@ -21,6 +22,7 @@ pub struct SuperVisionListConfig {
 impl_tryfrom_dop2struct!(SuperVisionListConfig);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 14, attribute = 1571)]
 pub struct SuperVisionListItem {
         /// Raw device ID (matches `deviceId` in Python)
         #[dop2field(1, Dop2Payloads::U8)]
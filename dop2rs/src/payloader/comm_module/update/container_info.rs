@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 397)]
 pub struct UpdateContainerInformation {
     #[dop2field(1, Dop2Payloads::U8)]
     update_state: u8,
@@ -2,18 +2,18 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 /// GLOBAL_EnumUpdateState – state of the firmware update process.
-#[repr(u8)]
+#[repr(u16)]
 #[derive(
     Debug,
     Clone,
-    TryFromPrimitive,
-    IntoPrimitive,
     PartialEq,
     Eq,
     EnumIter,
     EnumString,
     strum_macros::Display,
+    Dop2Enum,
 )]
+#[dop2(wire = E8)]
 pub enum UpdateState {
     Deactivated = 0,
     ReadyIdle = 1,
@@ -38,10 +38,10 @@ pub enum UpdateState {
     AbortedCouldNotCreateUpdateList = 20,
     AbortedFeatureMismatch = 21,
     Unknown = 255,
+    #[strum(to_string = "Other({0})")]
+    Other(u16),
 }
 
-crate::impl_tryfrom_wrapper!(UpdateState, E8);
-
 /// FT_UpdateControl (attribute 170 on unit 15)
 ///
 /// Mirrors `DOP2UpdateControl` in `MieleDop2Structures.py`:
@@ -50,6 +50,7 @@ crate::impl_tryfrom_wrapper!(UpdateState, E8);
 ///  - flash_accessible (field 3, bool)
 ///  - progress         (field 4, u16)
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 170)]
 pub struct UpdateControl {
     #[dop2field(1, Dop2Payloads::E8)]
     update_state: UpdateState,
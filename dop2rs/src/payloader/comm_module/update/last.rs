@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 199)]
 pub struct LastUpdateInfo {
     #[dop2field(1, Dop2Payloads::MString)]
     filename: String,
@@ -0,0 +1,20 @@
+use crate::payloader::prelude::*;
+use crate::Dop2ParseTreeExpressible;
+use crate::payloader::helper::types::GenericU8;
+
+/// Coffee maker parameters, field 4 of `CSContext`
+#[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+pub struct CSContextParametersCoffeeMaker {
+    #[dop2field(5, Dop2Payloads::MStruct)]
+    pub ceramic_valve: GenericU8,
+    #[dop2field(6, Dop2Payloads::MStruct)]
+    pub brewing_unit: GenericU8,
+    #[dop2field(7, Dop2Payloads::MStruct)]
+    pub pump: GenericU8,
+    #[dop2field(8, Dop2Payloads::MStruct)]
+    pub spout: GenericU8,
+    #[dop2field(12, Dop2Payloads::MStruct)]
+    pub fan: GenericU8,
+}
+
+impl_tryfrom_dop2struct!(CSContextParametersCoffeeMaker);
@@ -0,0 +1 @@
+pub mod context;
\ No newline at end of file
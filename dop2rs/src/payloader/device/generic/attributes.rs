@@ -2,15 +2,17 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 /// GLOBAL_EnumBeanContainerState - Bean container state
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum BeanContainerState {
     Unknown = 0,
     Empty = 1,
     Full = 2,
+    #[strum(to_string = "Other({0})")]
+    Other(u16),
 }
 
-crate::impl_tryfrom_wrapper!(BeanContainerState, E8);
 /// GLOBAL_StateBeanContainer - Bean container info with multiple compartments
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
 pub struct BeanContainerInfo {
@@ -24,20 +26,22 @@ pub struct BeanContainerInfo {
 
 impl_tryfrom_dop2struct!(BeanContainerInfo);
 /// GLOBAL_EnumDoorLock - Door lock state
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum DoorLock {
     Unlocked = 0,
     Locking = 1,
     Locked = 2,
     Unlocking = 3,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(DoorLock, E8);
-
 /// GLOBAL_EnumFasciaPanelState - Front panel state
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum FasciaPanelState {
     Undefined = 0,
     Closed = 1,
@@ -46,10 +50,10 @@ pub enum FasciaPanelState {
     Opening = 4,
     ErrorOpening = 5,
     ErrorClosing = 6,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(FasciaPanelState, E8);
-
 /// GLOBAL_DeviceAttributesCCA - Coffee machine device attributes
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
 pub struct DeviceAttributesCCA {
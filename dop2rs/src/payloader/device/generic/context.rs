@@ -6,7 +6,8 @@ use super::program_selection::context::PSAttributesCCA;
 use super::attributes::DeviceAttributesCCA;
 
 #[repr(u16)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum ShowMeHowId {
     None = 0,
     ReservedInvalid = 32767,
@@ -64,11 +65,11 @@ pub enum ShowMeHowId {
     CaOvUseWirelessFoodprobeNa30 = 97,
     CaOvUseWirelessFoodprobeR30R36 = 98,
     CaOvUseWirelessFoodprobeR48 = 99,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(ShowMeHowId, E16);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(leaves = [(1, 391), (2, 1585)])]
 pub struct DeviceContext {
     #[dop2field(1, Dop2Payloads::MStruct)]
     state: DeviceCombiState,
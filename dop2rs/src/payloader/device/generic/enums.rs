@@ -1,8 +1,9 @@
 use crate::payloader::prelude::*;
 
 /// GLOBAL_EnumMieleDeviceId - Miele device type identifiers
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum MieleDeviceId {
     NoUse = 0,
     WashingMachine = 1,
@@ -84,7 +85,7 @@ pub enum MieleDeviceId {
     DeviceTypeSteelcoReserved5 = 90,
     DeviceTypeMax = 91,
     DeviceTypeInvalid = 255,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(MieleDeviceId, E8);
-
@@ -1,6 +1,6 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
-use crate::payloader::helper::types::Dop2TimestampUtc;
+use crate::payloader::helper::types::Dop2Timestamp;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
 pub struct FailureListItem {
@@ -13,6 +13,7 @@ pub struct FailureListItem {
 impl_tryfrom_dop2struct!(FailureListItem);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 148)]
 pub struct FailureList {
     #[dop2field(1, Dop2Payloads::AStruct)]
     items: Vec<FailureListItem>,
@@ -21,6 +22,7 @@ pub struct FailureList {
 impl_tryfrom_dop2struct!(FailureList);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 117)]
 pub struct Failure {
     #[dop2field(1, Dop2Payloads::U32)]
     failure_code: u32,
@@ -29,7 +31,7 @@ pub struct Failure {
     #[dop2field(3, Dop2Payloads::U16)]
     occurrence_frequency: u16,
     #[dop2field(4, Dop2Payloads::U64)]
-    occurrence_time: Dop2TimestampUtc,
+    occurrence_time: Dop2Timestamp,
     #[dop2field(5, Dop2Payloads::U32)]
     operation_seconds: u32,
     #[dop2field(6, Dop2Payloads::U16)]
@@ -11,6 +11,7 @@ pub struct FeatureListOven {
 impl_tryfrom_dop2struct!(FeatureListOven);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 348)]
 pub struct FeatureList {
     #[dop2field(1, Dop2Payloads::E8)]
     device_id: MieleDeviceId,
@@ -2,8 +2,9 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 use super::supported_applications::SupportedApplications;
 
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum DeviceType {
     None,
     Washer,
@@ -19,10 +20,13 @@ pub enum DeviceType {
     Microwave,
     Oven,
     OvenMicrowaveCombo,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum ProtocolType {
     Unknown = 0,
     Uart = 1,
@@ -36,12 +40,12 @@ pub enum ProtocolType {
     DbusDop2 = 200,
     TodDop2 = 201,
     UsbDop2 = 202,
+    #[strum(to_string = "Other({0})")]
+    Other(u16),
 }
 
-crate::impl_tryfrom_wrapper!(DeviceType, E8);
-crate::impl_tryfrom_wrapper!(ProtocolType, E8);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 144)]
 pub struct DeviceIdent {
     #[dop2field(1, Dop2Payloads::E8)]
     device_type: DeviceType,
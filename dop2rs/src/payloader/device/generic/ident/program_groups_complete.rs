@@ -7,6 +7,7 @@ use crate::Dop2ParseTreeExpressible;
 /// * Field 1: array of smaller range descriptors
 /// * Field 2: array with a large bitmap covering all programs
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1599)]
 pub struct ProgramGroupsComplete {
     /// Range descriptors (start/end program ids with small bitmaps)
     #[dop2field(1, Dop2Payloads::AStruct)]
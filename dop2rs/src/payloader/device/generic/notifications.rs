@@ -1,8 +1,9 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum NotificationAckOption {
     None = 0,
     Ok,
@@ -16,10 +17,10 @@ pub enum NotificationAckOption {
     StepOver,
     SwitchOff,
     Change,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(NotificationAckOption, E8);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
 pub struct NotificationInfo {
     #[dop2field(1, Dop2Payloads::E16)]
@@ -59,6 +60,7 @@ pub struct QueryInfo {
 impl_tryfrom_dop2struct!(QueryInfo);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = [131, 392])]
 pub struct DeviceNotifications {
     #[dop2field(2, Dop2Payloads::AStruct)]
     messages: Vec<MessageInfo>,
@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::parser::DopArray;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1584)]
 pub struct ProgramList {
     #[dop2field(1, Dop2Payloads::U8)]
     valid: u8,
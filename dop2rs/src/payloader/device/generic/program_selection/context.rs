@@ -43,6 +43,7 @@ pub struct PSContextParametersOven {
 impl_tryfrom_dop2struct!(PSContextParametersOven);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1574)]
 pub struct PSContext {
     #[dop2field(4, Dop2Payloads::MStruct)]
     context_oven: PSContextParametersOven,
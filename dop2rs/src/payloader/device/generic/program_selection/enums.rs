@@ -1,7 +1,8 @@
 use crate::payloader::prelude::*;
 
 #[repr(u16)]
-#[derive(Debug, Clone, PartialEq, Eq, TryFromPrimitive, EnumIter, EnumString, strum_macros::Display, IntoPrimitive)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum ProgramIdOven {
     NoProgram = 0,
     DefrostBottom = 1,
@@ -98,12 +99,13 @@ pub enum ProgramIdOven {
     DryHotAirPlusWithSteam = 92,
     DrySteamOnly = 93,
     DryGrillWithSteam = 94,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(ProgramIdOven, E16);
-
-#[repr(u8)]
-#[derive(Debug, Clone, PartialEq, Eq, TryFromPrimitive, EnumIter, EnumString, strum_macros::Display, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum SelectionType {
     InitialAsConfigured = 0,
     InitialDefault = 1,
@@ -114,7 +116,7 @@ pub enum SelectionType {
     InitialDefaultViaSyndication = 11,
     ParametrizedTemperature = 12,
     Last = 13,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(SelectionType, E8);
-
@@ -3,13 +3,14 @@ use crate::Dop2ParseTreeExpressible;
 use super::enums::{ProgramIdOven, SelectionType};
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1577)]
 pub struct PsSelect {
     #[dop2field(1, Dop2Payloads::E16)]
-    pub(crate) program_id: ProgramIdOven,
+    pub program_id: ProgramIdOven,
     #[dop2field(2, Dop2Payloads::U16)]
-    pub(crate) selection_parameter: u16,
+    pub selection_parameter: u16,
     #[dop2field(3, Dop2Payloads::E8)]
-    pub(crate) selection_type: SelectionType,
+    pub selection_type: SelectionType,
 }
 
 impl_tryfrom_dop2struct!(PsSelect);
@@ -2,7 +2,8 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[repr(u16)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, EnumIter, EnumString, strum_macros::Display, IntoPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum UserRequestOven {
     Nop = 0,
     Start = 1,
@@ -33,11 +34,12 @@ pub enum UserRequestOven {
     WifiOff = 112,
     SetInteriorLightOn = 12141,
     SetInteriorLightOff = 12142,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(UserRequestOven, E16);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1583)]
 pub struct UserRequest {
     #[dop2field(1, Dop2Payloads::E16)]
     pub request_id: UserRequestOven,
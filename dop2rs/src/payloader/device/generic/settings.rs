@@ -2,7 +2,8 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[repr(u16)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum SfId {
     None = 0,
     TimeDisplay = 3,
@@ -545,11 +546,11 @@ pub enum SfId {
     ProWasherFlowmeterPulsesLagTime = 18183,
 
     ReservedInvalid = 32767,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(SfId, E16);
-
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 114)]
 pub struct SfValueList {
     #[dop2field(1, Dop2Payloads::U8)]
     valid_count: u8,
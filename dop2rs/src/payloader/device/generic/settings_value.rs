@@ -1,8 +1,10 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 use crate::payloader::device::generic::settings::SfId;
+use crate::payloader::units::Quantity;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 105)]
 pub struct SfValue {
     #[dop2field(1, Dop2Payloads::E16)]
     sf_id: SfId,
@@ -28,5 +30,33 @@ pub struct SfValue {
     fine_adjusted: bool,
 }
 
+impl SfValue {
+    /// The current value as a temperature, duration, mass, .. if the interpretation names one
+    pub fn quantity(&self) -> Option<Quantity> {
+        Quantity::from_raw(self.current_value.into(), self.value_interpretation)
+    }
+
+    /// Lowest and highest value the setting accepts
+    pub fn quantity_range(&self) -> Option<(Quantity, Quantity)> {
+        Some((
+            Quantity::from_raw(self.min.into(), self.value_interpretation)?,
+            Quantity::from_raw(self.max.into(), self.value_interpretation)?,
+        ))
+    }
+
+    /// Replaces the current value, which has to lie within `min..=max`
+    pub fn set_quantity(&mut self, quantity: &Quantity) -> Result<(), Dop2Error> {
+        let raw: i16 = quantity.to_raw(self.value_interpretation).map_err(|e| e.in_struct("SfValue"))?;
+        if raw < self.min || raw > self.max {
+            return Err(Dop2Error::invalid_conversion(
+                "SfValue",
+                format!("{} outside of {}..={} as {}", quantity.describe(self.value_interpretation), self.min, self.max, self.value_interpretation),
+            ));
+        }
+        self.current_value = raw;
+        Ok(())
+    }
+}
+
 impl_tryfrom_dop2struct!(SfValue);
 
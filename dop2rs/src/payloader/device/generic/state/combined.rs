@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 1586)]
 pub struct DeviceCombiState {
         #[dop2field(1, Dop2Payloads::E8)]
         appliance_state : ApplianceState,
@@ -13,8 +14,9 @@ pub struct DeviceCombiState {
 
 impl_tryfrom_dop2struct!(DeviceCombiState);
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, TryFromPrimitive, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum ApplianceState
 {
     Unknown = 0,
@@ -29,10 +31,12 @@ pub enum ApplianceState
     Standby = 9,
     Supervisory = 10,
     ShowWindow = 11,
+    Other(u16),
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, TryFromPrimitive, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum OperationState
 {
     Unknown = 0,
@@ -52,10 +56,12 @@ pub enum OperationState
     ColdRising = 21,
     NormalRinsing = 22,
     EmergencyStop = 32,
+    Other(u16),
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, TryFromPrimitive, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum ProcessState
 {
     Unknown = 0,
@@ -64,10 +70,6 @@ pub enum ProcessState
     ProgramStarted = 3,
     ProgramRunning = 4,
     ProgramStop = 5,
+    Other(u16),
 }
 
-crate::impl_tryfrom_wrapper!(ApplianceState, E8);
-
-crate::impl_tryfrom_wrapper!(ProcessState, E8);
-
-crate::impl_tryfrom_wrapper!(OperationState, E8);
@@ -2,6 +2,8 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 use crate::payloader::helper::types::{AnnotatedBool, GenericU8, GenericU16};
 use crate::payloader::device::generic::program_selection::enums::ProgramIdOven;
+#[cfg(feature = "coffee")]
+use crate::payloader::device::coffee::context::CSContextParametersCoffeeMaker;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
 pub struct CSContextParametersOven {
@@ -39,23 +41,9 @@ pub struct CSContextParametersWasher {
 
 impl_tryfrom_dop2struct!(CSContextParametersWasher);
 
-#[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
-pub struct CSContextParametersCoffeeMaker {
-    #[dop2field(5, Dop2Payloads::MStruct)]
-    ceramic_valve: GenericU8,
-    #[dop2field(6, Dop2Payloads::MStruct)]
-    brewing_unit: GenericU8,
-    #[dop2field(7, Dop2Payloads::MStruct)]
-    pump: GenericU8,
-    #[dop2field(8, Dop2Payloads::MStruct)]
-    spout: GenericU8,
-    #[dop2field(12, Dop2Payloads::MStruct)]
-    fan: GenericU8,
-}
-
-impl_tryfrom_dop2struct!(CSContextParametersCoffeeMaker);
 // TODO: Make these optional where needed
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 154)]
 pub struct CSContext {
     #[dop2field(1, Dop2Payloads::E16)]
     pub program_id: ProgramIdOven,
@@ -63,23 +51,26 @@ pub struct CSContext {
     pub context_washer: Option<CSContextParametersWasher>,
     #[dop2field(3, Dop2Payloads::MStruct)]
     pub context_oven: Option<CSContextParametersOven>,
-  //  #[dop2field(4, Dop2Payloads::MStruct)]
-   // context_coffee_maker: CSContextParametersCoffeeMaker,
+    #[cfg(feature = "coffee")]
+    #[dop2field(4, Dop2Payloads::MStruct)]
+    pub context_coffee_maker: Option<CSContextParametersCoffeeMaker>,
 }
 
 impl_tryfrom_dop2struct!(CSContext);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 174)]
 pub struct CSBarcode {
     #[dop2field(1, Dop2Payloads::ArrayU8)]
-    pub(crate) partname: String,
+    partname: String,
     #[dop2field(2, Dop2Payloads::ArrayU8)]
-    pub(crate) barcode: DopArray<u8>,
+    barcode: DopArray<u8>,
 }
 
 impl_tryfrom_dop2struct!(CSBarcode);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 173)]
 pub struct PartName {
     #[dop2field(1, Dop2Payloads::ArrayU8)]
     pub part_name: String,
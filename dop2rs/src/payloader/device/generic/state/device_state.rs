@@ -18,6 +18,7 @@ pub struct RemoteEnable {
 impl_tryfrom_dop2struct!(RemoteEnable);
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 256)]
 pub struct DeviceState {
     #[dop2field(1, Dop2Payloads::E8)]
     main_state: E8,
@@ -61,6 +62,8 @@ pub struct DeviceState {
     field20: i32,
     #[dop2field(21, Dop2Payloads::I32)]
     field21: i32,
+    #[dop2(extra)]
+    extra: Dop2ExtraFields,
 }
 
 impl_tryfrom_dop2struct!(DeviceState);
@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 119)]
 pub struct CSHoursOfOperation {
     #[dop2field(1, Dop2Payloads::U32)]
     hours_of_operation: u32,
@@ -1,3 +1,7 @@
+#[cfg(feature = "oven")]
 pub mod oven;
 pub mod generic;
-pub mod washer;
\ No newline at end of file
+#[cfg(feature = "washer")]
+pub mod washer;
+#[cfg(feature = "coffee")]
+pub mod coffee;
@@ -2,7 +2,8 @@ use crate::payloader::prelude::*;
 
 /// Notification information identifier for oven/appliance instructions
 #[repr(u16)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum NotificationInfoId {
     Void = 0,
     AllFunctionsResetToDefault = 1,
@@ -505,13 +506,13 @@ pub enum NotificationInfoId {
     SmartFoodIdSupportedFoods = 32011,
     SmartFoodIdCheckNetworkConnection = 32012,
     SmartFoodIdCheckCameraGlass = 32013,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(NotificationInfoId, E16);
-
 /// Notification message identifier for oven/appliance status messages
 #[repr(u16)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E16)]
 pub enum NotificationMessageId {
     Void = 0,
     DoorUnlocked = 1,
@@ -611,7 +612,6 @@ pub enum NotificationMessageId {
     CupTasting = 24023,
     Flooding = 24024,
     SizeConstraint = 32767,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(NotificationMessageId, E16);
-
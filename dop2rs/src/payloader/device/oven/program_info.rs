@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 213)]
 pub struct ProgramInfoOven 
 {
 #[dop2field(1, Dop2Payloads::U8 )]
@@ -11,8 +12,10 @@ step_number : u8,
     current_step : u8,
 
 #[dop2field(5, Dop2Payloads::Boolean )]
-    start_delay : bool, // TODO: add remaining fields
+    start_delay : bool,
 
+#[dop2(extra)]
+    extra : Dop2ExtraFields,
 }
 
 impl_tryfrom_dop2struct!(ProgramInfoOven);
\ No newline at end of file
@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::helper::types::E16;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 257)]
 pub struct ProgramInstructionsCA {
     #[dop2field(1, Dop2Payloads::E16)]
     pub info_id: E16,
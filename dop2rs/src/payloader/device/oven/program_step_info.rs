@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 214)]
 pub struct ProgramStepInfoOven 
 {
     #[dop2field(1, Dop2Payloads::U8 )]
@@ -12,7 +13,7 @@ pub struct ProgramStepInfoOven
     #[dop2field(3, Dop2Payloads::U16 )]
     operation_mode : u16,
     #[dop2field(4, Dop2Payloads::U16 )]
-    temperature_setpoint : u8,
+    temperature_setpoint : u16,
     #[dop2field(5, Dop2Payloads::U8 )]
     set_grill_level : u8,
     #[dop2field(6, Dop2Payloads::U8 )]
@@ -20,7 +21,9 @@ pub struct ProgramStepInfoOven
     #[dop2field(7, Dop2Payloads::U32 )]
     duration : u32,
     #[dop2field(8, Dop2Payloads::U16 )]
-    moistset_core_temperature : u16, // TODO: add remaining fields
+    moistset_core_temperature : u16,
+    #[dop2(extra)]
+    extra : Dop2ExtraFields,
 }
 
 impl_tryfrom_dop2struct!(ProgramStepInfoOven);
@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::helper::types::{AnnotatedBool};
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 6192)]
 pub struct ActuatorData {
     #[dop2field(1, Dop2Payloads::MStruct)]
     pub heater1: AnnotatedBool,
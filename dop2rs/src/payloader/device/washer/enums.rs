@@ -1,19 +1,20 @@
 use crate::payloader::prelude::*;
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum DetergentType {
     NoDetergent = 0,
     UltraPhase1 = 1,
     UltraPhase2 = 2,
     UltraWhite = 3,
     UltraColor = 4,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(DetergentType, E8);
-
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum DryingStep {
     ExtraDry = 0,
     NormalPlus = 1,
@@ -23,22 +24,22 @@ pub enum DryingStep {
     HandIron2 = 5,
     MachineIron = 6,
     HygieneDry = 7,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(DryingStep, E8);
-
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum Light {
     NotSupported = 0,
     Enabled = 1,
     Disabled = 2,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(Light, E8);
-
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum ProgramType {
     BuiltInFunction = 1,
     UserDefined = 2,
@@ -46,7 +47,6 @@ pub enum ProgramType {
     CleaningProgram = 4,
     CustomerService = 5,
     Helper = 6,
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(ProgramType, E8);
-
@@ -1,8 +1,10 @@
 use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
+use crate::payloader::units::{annotated_quantities, Quantity};
 use crate::payloader::helper::types::{AnnotatedU8, AnnotatedU16, AnnotatedI16, AnnotatedI32, AnnotatedBool, GenericU8, GenericU16};
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 6195)]
 pub struct Process {
     #[dop2field(1, Dop2Payloads::MStruct)]
     pub block_number: GenericU8,
@@ -100,4 +102,11 @@ pub struct Process {
     pub tb_kg_result_integral95: AnnotatedU16,
 }
 
+impl Process {
+    /// Every field whose interpretation names a temperature, duration, mass, ..
+    pub fn quantities(&self) -> Vec<(&'static str, Quantity)> {
+        annotated_quantities(self)
+    }
+}
+
 impl_tryfrom_dop2struct!(Process);
@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::helper::types::{AnnotatedU8, AnnotatedU16, AnnotatedI16, AnnotatedBool};
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 6193)]
 pub struct Sensor {
     #[dop2field(1, Dop2Payloads::MStruct)]
     pub water_level: AnnotatedU16,
@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 2, attribute = 6194)]
 pub struct SoftwareBuild {
     #[dop2field(1, Dop2Payloads::MString)]
     date: String,
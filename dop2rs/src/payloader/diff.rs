@@ -0,0 +1,279 @@
+// Structural diff between two DOP2 parse trees
+
+use crate::attribute_registry::AttributeRegistry;
+use crate::payloader::error::{Dop2Error, Dop2Path, Dop2PathSegment};
+use crate::payloader::helper::types::{E32, E64};
+use crate::payloader::root::{Dop2FieldInfo, Dop2Payloads, Dop2Struct, RootNode};
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dop2ChangeKind {
+    Added,
+    Removed,
+    /// The value or the type tag differs
+    Changed,
+}
+
+/// One field or array element that differs between two trees
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dop2Change {
+    pub kind: Dop2ChangeKind,
+    #[serde(serialize_with = "serialize_dotted")]
+    pub path: Dop2Path,
+    /// Rust field path on the registered type, e.g. `DeviceState.remaining_time`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Dop2Payloads>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Dop2Payloads>,
+}
+
+/// A Rust field of the registered type whose decoded value differs.
+///
+/// The registered types are not `Serialize`, so `old` and `new` are their `Debug` text,
+/// in JSON as well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dop2TypedChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// Changes from one reading of an attribute to the next.
+///
+/// `Display` prints one line per change, `Serialize` gives the same as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dop2Diff {
+    pub unit: u16,
+    pub attribute: u16,
+    /// Registered type of the attribute, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<&'static str>,
+    pub changes: Vec<Dop2Change>,
+    /// The same readings compared after decoding them into the registered type
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub typed_changes: Vec<Dop2TypedChange>,
+    /// Why a reading did not decode into the registered type, which leaves `typed_changes` empty
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "serialize_error")]
+    pub typed_error: Option<Dop2Error>,
+}
+
+/// Why two readings could not be diffed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dop2DiffError {
+    /// The readings are of different leaves, so their fields mean different things
+    DifferentLeaves { old: (u16, u16), new: (u16, u16) },
+}
+
+impl fmt::Display for Dop2DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dop2DiffError::DifferentLeaves { old, new } => {
+                write!(f, "cannot diff {}/{} against {}/{}", old.0, old.1, new.0, new.1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Dop2DiffError {}
+
+impl Dop2Diff {
+    /// Diffs two readings of the same unit and attribute, both as parse trees and
+    /// on the type `registry` has for the attribute. A reading that does not decode into
+    /// that type still gets the parse tree diff, with the reason in `typed_error`.
+    pub fn new(old: &RootNode, new: &RootNode, registry: &AttributeRegistry) -> Result<Dop2Diff, Dop2DiffError> {
+        if (old.unit, old.attribute) != (new.unit, new.attribute) {
+            return Err(Dop2DiffError::DifferentLeaves {
+                old: (old.unit, old.attribute),
+                new: (new.unit, new.attribute),
+            });
+        }
+        let schema = registry.schema(new.unit, new.attribute);
+        let mut changes = diff_structs(&old.root_struct, &new.root_struct);
+        if let Some((type_name, fields)) = schema {
+            for change in &mut changes {
+                change.field = typed_path(type_name, fields, &change.path);
+            }
+        }
+        let (typed_changes, typed_error) = match registry.typed_diff(new.unit, new.attribute, old.root_struct.clone(), new.root_struct.clone()) {
+            Some(Ok(typed_changes)) => (typed_changes, None),
+            Some(Err(e)) => (Vec::new(), Some(e)),
+            None => (Vec::new(), None),
+        };
+        Ok(Dop2Diff {
+            unit: new.unit,
+            attribute: new.attribute,
+            type_name: schema.map(|(type_name, _)| type_name),
+            changes,
+            typed_changes,
+            typed_error,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Dop2Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.unit, self.attribute)?;
+        if let Some(type_name) = self.type_name {
+            write!(f, " ({})", type_name)?;
+        }
+        writeln!(f, ": {} changes", self.changes.len())?;
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        for change in &self.typed_changes {
+            writeln!(f, "= {}.{}: {} -> {}", self.type_name.unwrap_or_default(), change.field, change.old, change.new)?;
+        }
+        if let Some(e) = &self.typed_error {
+            writeln!(f, "! {}: {}", self.type_name.unwrap_or_default(), e)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Dop2Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            Dop2ChangeKind::Added => '+',
+            Dop2ChangeKind::Removed => '-',
+            Dop2ChangeKind::Changed => '~',
+        };
+        write!(f, "{} {}", sign, self.path.dotted())?;
+        if let Some(field) = &self.field {
+            write!(f, " ({})", field)?;
+        }
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, ": {} -> {}", old, new),
+            (Some(value), None) | (None, Some(value)) => write!(f, ": {}", value),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Field by field comparison in the order of `old`, followed by the fields only `new` has
+pub fn diff_structs(old: &Dop2Struct, new: &Dop2Struct) -> Vec<Dop2Change> {
+    let mut changes = Vec::new();
+    diff_into(old, new, &mut Vec::new(), &mut changes);
+    changes
+}
+
+fn diff_into(old: &Dop2Struct, new: &Dop2Struct, prefix: &mut Vec<Dop2PathSegment>, changes: &mut Vec<Dop2Change>) {
+    for old_field in &old.fields {
+        prefix.push(Dop2PathSegment::Field(old_field.field_index));
+        match new.field(old_field.field_index) {
+            Some(new_field) => diff_values(&old_field.value, &new_field.value, prefix, changes),
+            None => changes.push(change(prefix, Some(&old_field.value), None)),
+        }
+        prefix.pop();
+    }
+    for new_field in &new.fields {
+        if old.field(new_field.field_index).is_none() {
+            prefix.push(Dop2PathSegment::Field(new_field.field_index));
+            changes.push(change(prefix, None, Some(&new_field.value)));
+            prefix.pop();
+        }
+    }
+}
+
+fn diff_values(old: &Dop2Payloads, new: &Dop2Payloads, prefix: &mut Vec<Dop2PathSegment>, changes: &mut Vec<Dop2Change>) {
+    match (old, new) {
+        (Dop2Payloads::MStruct(old), Dop2Payloads::MStruct(new)) => diff_into(old, new, prefix, changes),
+        (Dop2Payloads::AStruct(old), Dop2Payloads::AStruct(new)) => {
+            let old = old.elements.iter().cloned().map(Dop2Payloads::MStruct).collect();
+            let new = new.elements.iter().cloned().map(Dop2Payloads::MStruct).collect();
+            diff_elements(old, new, prefix, changes);
+        }
+        _ if old == new => {}
+        _ => match (array_elements(old), array_elements(new)) {
+            (Some(old_elements), Some(new_elements)) if std::mem::discriminant(old) == std::mem::discriminant(new) => {
+                diff_elements(old_elements, new_elements, prefix, changes)
+            }
+            _ => changes.push(change(prefix, Some(old), Some(new))),
+        },
+    }
+}
+
+fn diff_elements(old: Vec<Dop2Payloads>, new: Vec<Dop2Payloads>, prefix: &mut Vec<Dop2PathSegment>, changes: &mut Vec<Dop2Change>) {
+    for i in 0..old.len().max(new.len()) {
+        prefix.push(Dop2PathSegment::Element(i));
+        match (old.get(i), new.get(i)) {
+            (Some(old), Some(new)) => diff_values(old, new, prefix, changes),
+            (old, new) => changes.push(change(prefix, old, new)),
+        }
+        prefix.pop();
+    }
+}
+
+fn change(path: &[Dop2PathSegment], old: Option<&Dop2Payloads>, new: Option<&Dop2Payloads>) -> Dop2Change {
+    let kind = match (old, new) {
+        (Some(_), Some(_)) => Dop2ChangeKind::Changed,
+        (Some(_), None) => Dop2ChangeKind::Removed,
+        _ => Dop2ChangeKind::Added,
+    };
+    Dop2Change {
+        kind,
+        path: Dop2Path(path.to_vec()),
+        field: None,
+        old: old.cloned(),
+        new: new.cloned(),
+    }
+}
+
+/// Elements of a scalar array, each as the payload of its element kind
+fn array_elements(payload: &Dop2Payloads) -> Option<Vec<Dop2Payloads>> {
+    fn each<T: Clone>(elements: &[T], wrap: impl Fn(T) -> Dop2Payloads) -> Option<Vec<Dop2Payloads>> {
+        Some(elements.iter().cloned().map(wrap).collect())
+    }
+    match payload {
+        Dop2Payloads::ArrayBool(a) => each(&a.elements, Dop2Payloads::Boolean),
+        Dop2Payloads::ArrayU8(a) => each(&a.elements, Dop2Payloads::U8),
+        Dop2Payloads::ArrayI8(a) => each(&a.elements, Dop2Payloads::I8),
+        Dop2Payloads::ArrayE8(a) => each(&a.elements, Dop2Payloads::E8),
+        Dop2Payloads::ArrayU16(a) => each(&a.elements, Dop2Payloads::U16),
+        Dop2Payloads::ArrayI16(a) => each(&a.elements, Dop2Payloads::I16),
+        Dop2Payloads::ArrayE16(a) => each(&a.elements, Dop2Payloads::E16),
+        Dop2Payloads::ArrayU32(a) => each(&a.elements, Dop2Payloads::U32),
+        Dop2Payloads::ArrayI32(a) => each(&a.elements, Dop2Payloads::I32),
+        Dop2Payloads::ArrayE32(a) => each(&a.elements, |v| Dop2Payloads::E32(E32(v))),
+        Dop2Payloads::ArrayU64(a) => each(&a.elements, Dop2Payloads::U64),
+        Dop2Payloads::ArrayI64(a) => each(&a.elements, Dop2Payloads::I64),
+        Dop2Payloads::ArrayE64(a) => each(&a.elements, |v| Dop2Payloads::E64(E64(v))),
+        Dop2Payloads::ArrayF32(a) => each(&a.elements, Dop2Payloads::F32),
+        Dop2Payloads::ArrayF64(a) => each(&a.elements, Dop2Payloads::F64),
+        _ => None,
+    }
+}
+
+/// `DeviceState.remaining_time`, or `None` once the path leaves the known fields
+fn typed_path(type_name: &str, fields: &[Dop2FieldInfo], path: &Dop2Path) -> Option<String> {
+    let mut text = type_name.to_string();
+    let mut fields = Some(fields);
+    for segment in path.segments() {
+        match segment {
+            Dop2PathSegment::Field(index) => {
+                let info = fields?.iter().find(|info| info.index == *index)?;
+                text.push('.');
+                text.push_str(info.name);
+                fields = info.nested;
+            }
+            Dop2PathSegment::Element(index) => text.push_str(&format!("[{}]", index)),
+            Dop2PathSegment::Unit(_) | Dop2PathSegment::Attribute(_) => {}
+        }
+    }
+    Some(text)
+}
+
+fn serialize_error<S: Serializer>(error: &Option<Dop2Error>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(error.as_ref().expect("skipped when None"))
+}
+
+fn serialize_dotted<S: Serializer>(path: &Dop2Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.dotted())
+}
@@ -0,0 +1,165 @@
+// Annotated hex dump of DOP2 frames, every byte next to its meaning
+
+use crate::attribute_registry::AttributeRegistry;
+use crate::payloader::error::Dop2Error;
+use crate::payloader::parser::Dop2Parser;
+use crate::payloader::root::{Dop2FieldInfo, Dop2PayloadsKind, DopPadding, TaggedDopField};
+use num_enum::TryFromPrimitive;
+use std::fmt::Write;
+
+const BYTES_PER_LINE: usize = 8;
+
+/// Dumps one or more concatenated frames as `offset  hex  meaning` lines.
+///
+/// Header words, field headers, array counts, struct boundaries and padding each
+/// get their own line. Fields of attributes with a type in `registry` are named
+/// after their Rust struct fields. Bytes that cannot be decoded are dumped as
+/// plain hex after the error.
+pub fn annotated_dump(bytes: &[u8], registry: &AttributeRegistry) -> String {
+    let mut dump = Dump {
+        bytes,
+        out: String::new(),
+        depth: 0,
+        printed_to: 0,
+    };
+    let mut parser = Dop2Parser::new(bytes);
+    while !parser.is_empty() {
+        if let Err(error) = dump.frame(&mut parser, registry) {
+            dump.depth = 0;
+            dump.line(dump.printed_to, 0, &format!("error: {}", error));
+            dump.line(dump.printed_to, bytes.len() - dump.printed_to, "undecoded");
+            break;
+        }
+    }
+    dump.out
+}
+
+struct Dump<'a> {
+    bytes: &'a [u8],
+    out: String,
+    depth: usize,
+    /// Everything before this offset has been printed
+    printed_to: usize,
+}
+
+impl Dump<'_> {
+    fn line(&mut self, offset: usize, length: usize, text: &str) {
+        let indent = "  ".repeat(self.depth);
+        let chunk = &self.bytes[offset..offset + length];
+        if chunk.is_empty() {
+            let _ = writeln!(self.out, "{:04x}  {:23}  {}{}", offset, "", indent, text);
+        }
+        for (i, part) in chunk.chunks(BYTES_PER_LINE).enumerate() {
+            let hex: Vec<String> = part.iter().map(|b| format!("{:02x}", b)).collect();
+            let text = if i == 0 { text } else { "" };
+            let line = format!("{:04x}  {:23}  {}{}", offset + i * BYTES_PER_LINE, hex.join(" "), indent, text);
+            let _ = writeln!(self.out, "{}", line.trim_end());
+        }
+        self.printed_to = offset + length;
+    }
+
+    fn word(&mut self, parser: &mut Dop2Parser, name: &str) -> Result<u16, Dop2Error> {
+        let offset = parser.position();
+        let value = parser.take_u16()?;
+        self.line(offset, 2, &format!("{} {}", name, value));
+        Ok(value)
+    }
+
+    fn frame(&mut self, parser: &mut Dop2Parser, registry: &AttributeRegistry) -> Result<(), Dop2Error> {
+        let start = parser.position();
+        let declared_length = self.word(parser, "declared_length")?;
+        parser.set_frame_end(start + 2 + usize::from(declared_length));
+        let unit = self.word(parser, "unit")?;
+
+        let offset = parser.position();
+        let attribute = parser.take_u16()?;
+        let schema = registry.schema(unit, attribute);
+        let type_name = schema.map(|(name, _)| format!(" ({})", name)).unwrap_or_default();
+        self.line(offset, 2, &format!("attribute {}{}", attribute, type_name));
+
+        self.word(parser, "idx1")?;
+        self.word(parser, "idx2")?;
+        self.dop2_struct(parser, schema.map(|(_, fields)| fields))?;
+
+        let content_length = parser.position() - start;
+        if content_length != 2 + usize::from(declared_length) {
+            self.line(
+                parser.position(),
+                0,
+                &format!("declared_length {} but the frame holds {} bytes", declared_length, content_length - 2),
+            );
+        }
+
+        // padding up to the next frame, or everything if only padding is left
+        let offset = parser.position();
+        let rest = &self.bytes[offset..];
+        let expected = usize::from(DopPadding::after(content_length).bytes_of_padding).min(rest.len());
+        let padding = if rest.iter().all(|b| *b == 0x20) {
+            rest.len()
+        } else {
+            rest[..expected].iter().take_while(|b| **b == 0x20).count()
+        };
+        if padding > 0 {
+            parser.take(padding)?;
+            self.line(offset, padding, &format!("padding, {} bytes", padding));
+        }
+        Ok(())
+    }
+
+    fn dop2_struct(&mut self, parser: &mut Dop2Parser, fields: Option<&[Dop2FieldInfo]>) -> Result<(), Dop2Error> {
+        let offset = parser.position();
+        parser.enter(offset)?;
+        let count = parser.take_u16()?;
+        parser.add_elements(offset, count)?;
+        self.line(offset, 2, &format!("struct, {} fields {{", count));
+        self.depth += 1;
+        for _ in 0..count {
+            self.field(parser, fields)?;
+        }
+        self.depth -= 1;
+        parser.leave();
+        self.line(parser.position(), 0, "}");
+        Ok(())
+    }
+
+    fn field(&mut self, parser: &mut Dop2Parser, fields: Option<&[Dop2FieldInfo]>) -> Result<(), Dop2Error> {
+        let start = parser.position();
+        let index = parser.take_u16()?;
+        let tag = parser.take_u8()?;
+        let info = fields.and_then(|fields| fields.iter().find(|info| info.index == index));
+        let name = info.map(|info| format!(" {}", info.name)).unwrap_or_default();
+        let nested = info.and_then(|info| info.nested);
+
+        match Dop2PayloadsKind::try_from_primitive(tag) {
+            Ok(Dop2PayloadsKind::MStruct) => {
+                self.line(start, 3, &format!("field {}{}: MStruct", index, name));
+                self.dop2_struct(parser, nested)
+            }
+            Ok(Dop2PayloadsKind::AStruct) => {
+                self.line(start, 3, &format!("field {}{}: AStruct", index, name));
+                let offset = parser.position();
+                let count = parser.take_u16()?;
+                parser.add_elements(offset, count)?;
+                self.line(offset, 2, &format!("{} elements", count));
+                for element in 0..count {
+                    self.line(parser.position(), 0, &format!("element {}", element));
+                    self.dop2_struct(parser, nested)?;
+                }
+                Ok(())
+            }
+            _ => {
+                parser.rewind(start);
+                let field = TaggedDopField::parse(parser)?;
+                let count = match self.bytes.get(start + 3..start + 5) {
+                    Some(&[high, low]) if field.tag.is_array() => {
+                        format!(" ({} elements)", u16::from_be_bytes([high, low]))
+                    }
+                    _ => String::new(),
+                };
+                let length = parser.position() - start;
+                self.line(start, length, &format!("field {}{}: {}{}", index, name, field.value, count));
+                Ok(())
+            }
+        }
+    }
+}
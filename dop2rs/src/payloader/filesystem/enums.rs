@@ -1,7 +1,8 @@
 use crate::payloader::prelude::*;
 
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum FileOperation {
     Open = 0,
     Write = 1,
@@ -13,12 +14,13 @@ pub enum FileOperation {
     Read = 7,
     Finalize = 8,
     Invalid = 255,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(FileOperation, E8);
-
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum FileOperationStatus {
     NoError = 0,
     CannotOpen = 1,
@@ -30,12 +32,13 @@ pub enum FileOperationStatus {
     OutOfMemory = 7,
     ReadInProgress = 8,
     Invalid = 255,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(FileOperationStatus, E8);
-
-#[repr(u8)]
-#[derive(Debug, Clone, TryFromPrimitive, IntoPrimitive, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display)]
+#[repr(u16)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum FileAccessMode {
     NotAccessible = 0,
     Read = 1,
@@ -45,7 +48,7 @@ pub enum FileAccessMode {
     WriteDirectory = 5,
     ReadWriteDirectory = 6,
     Invalid = 255,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(FileAccessMode, E8);
-
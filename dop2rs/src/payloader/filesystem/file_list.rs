@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::filesystem::enums::FileAccessMode;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 1, attribute = 333)]
 pub struct FileList {
     #[dop2field(1, Dop2Payloads::MString)]
     filename: String,
@@ -3,6 +3,7 @@ use crate::Dop2ParseTreeExpressible;
 use crate::payloader::filesystem::enums::FileOperation;
 
 #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 1590)]
 pub struct FileWrite {
     #[dop2field(1, Dop2Payloads::E8)]
     file_operation: FileOperation,
@@ -2,6 +2,7 @@ use crate::payloader::prelude::*;
 use crate::Dop2ParseTreeExpressible;
 
 #[derive(Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 287)]
 pub struct RsaKey {
     #[dop2field(1, Dop2Payloads::ArrayU8)]
     key: DopArray<u8>,
@@ -4,6 +4,7 @@ use crate::payloader::filesystem::enums::FileOperation;
 use crate::payloader::filesystem::enums::FileOperationStatus;
 
 #[derive(Clone, PartialEq, Eq, AssocTypes)]
+#[dop2(unit = 15, attribute = 336)]
 pub struct FileTransfer {
     #[dop2field(1, Dop2Payloads::MString)]
     file_name: String,
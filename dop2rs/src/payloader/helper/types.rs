@@ -1,55 +1,36 @@
+#[cfg(feature = "chrono")]
 use chrono::{DateTime, Utc};
 use core::fmt;
-use dop2marshal::AssocTypes;
-use derive_more::From;
 use crate::payloader::prelude::*;
-use crate::{MakeAnnotatedValueType, MakeGenericValueType, impl_to_bytes, impl_from_bytes};
-use crate::payloader::root::Dop2ParseTreeExpressible;
-use crate::payloader::parser::{DopArray, ToDop2Bytes, Dop2PayloadExpressible, Dop2Parser};
-use crate::newtype_int;
+use crate::payloader::parser::DopArray;
 
+/// Seconds since the Unix epoch, as read from a `U64` field
+#[cfg(feature = "chrono")]
+pub type Dop2Timestamp = Dop2TimestampUtc;
+/// Seconds since the Unix epoch, as read from a `U64` field
+#[cfg(not(feature = "chrono"))]
+pub type Dop2Timestamp = u64;
+
+#[cfg(feature = "chrono")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Dop2TimestampUtc(pub DateTime<Utc>);
 
+#[cfg(feature = "chrono")]
 impl TryFrom<u64> for Dop2TimestampUtc {
-    type Error = &'static str;
+    type Error = Dop2Error;
 
     fn try_from(value: u64) -> Result<Self, Self::Error> {
+        let out_of_range = || Dop2Error::invalid_conversion("Dop2TimestampUtc", format!("out of range timestamp {}", value));
         if value > i64::MAX as u64 {
-            return Err("timestamp out of range");
+            return Err(out_of_range());
         }
 
         let dt = DateTime::from_timestamp(value as i64, 0)
-            .ok_or("timestamp out of range")?;
+            .ok_or_else(out_of_range)?;
         Ok(Dop2TimestampUtc(dt))
     }
 }
-impl TryFrom<DopArray<u8>> for String {
-    type Error = &'static str;
-
-    fn try_from(value: DopArray<u8>) -> Result<Self, Self::Error> {
-        // Convert only up to `count` elements.
-        if value.count > value.elements.len() as u16 {
-            return Err("count field exceeds number of elements in DopArray<u8>");
-        }
-        let ascii_bytes = &value.elements[..value.count as usize];
-        match std::str::from_utf8(ascii_bytes) {
-            Ok(s) => Ok(s.to_string()),
-            Err(_) => Err("DopArray<u8> contains invalid ASCII/UTF-8"),
-        }
-    }
-}
-
-impl From<String> for DopArray<u8> {
-    fn from(value: String) -> Self {
-        let ascii_bytes = value.as_bytes();
-        DopArray {
-            count: ascii_bytes.len() as u16,
-            elements: ascii_bytes.to_vec(),
-        }
-    }
-}
-
+#[cfg(feature = "chrono")]
 impl From<Dop2TimestampUtc> for u64 {
     fn from(value: Dop2TimestampUtc) -> Self {
         return value.0.timestamp() as u64;
@@ -80,11 +61,11 @@ impl fmt::Debug for Dop2MacAddress {
 }
 
 impl TryFrom<DopArray<u8>> for Dop2MacAddress {
-    type Error = &'static str;
+    type Error = Dop2Error;
 
     fn try_from(value: DopArray<u8>) -> Result<Self, Self::Error> {
         if value.count != 8 || value.elements.len() != 8 {
-            return Err("Dop2MacAddress expects a DopArray<u8> with count == 8");
+            return Err(Dop2Error::invalid_conversion("Dop2MacAddress", format!("DopArray<u8> with count {}, expected 8", value.count)));
         }
 
         let mut bytes = [0u8; 8];
@@ -122,11 +103,11 @@ impl fmt::Debug for Dop2IpV4Adress {
 }
 
 impl TryFrom<DopArray<u8>> for Dop2IpV4Adress {
-    type Error = &'static str;
+    type Error = Dop2Error;
 
     fn try_from(value: DopArray<u8>) -> Result<Self, Self::Error> {
         if value.count != 4 || value.elements.len() != 4 {
-            return Err("Dop2IpV4Adress expects a DopArray<u8> with count == 4");
+            return Err(Dop2Error::invalid_conversion("Dop2IpV4Adress", format!("DopArray<u8> with count {}, expected 4", value.count)));
         }
 
         let mut bytes = [0u8; 4];
@@ -144,49 +125,17 @@ impl From<Dop2IpV4Adress> for DopArray<u8> {
     }
 }
 
-MakeAnnotatedValueType!(AnnotatedU8, U8, u8);
-MakeAnnotatedValueType!(AnnotatedU16, U16, u16);
-MakeAnnotatedValueType!(AnnotatedI16, I16, i16);
-MakeAnnotatedValueType!(AnnotatedI32, I32, i32);
-MakeAnnotatedValueType!(AnnotatedU64, U64, u64);
-MakeAnnotatedValueType!(AnnotatedBool, Boolean, bool);
-MakeAnnotatedValueType!(AnnotatedTimeStamp, U64, Dop2TimestampUtc);
-
-MakeGenericValueType!(GenericU8, U8, u8);
-MakeGenericValueType!(GenericU16, U16, u16);
-
-impl_tryfrom_dop2struct!(AnnotatedU8);
-impl_tryfrom_dop2struct!(AnnotatedU16);
-impl_tryfrom_dop2struct!(AnnotatedI16);
-impl_tryfrom_dop2struct!(AnnotatedI32);
-impl_tryfrom_dop2struct!(AnnotatedU64);
-impl_tryfrom_dop2struct!(AnnotatedBool);
-impl_tryfrom_dop2struct!(AnnotatedTimeStamp);
-
-impl_tryfrom_dop2struct!(GenericU8);
-impl_tryfrom_dop2struct!(GenericU16);
-
-// Implement traits for primitive integer types
-impl_to_bytes!(u8);
-impl_to_bytes!(i8);
-impl_to_bytes!(u16);
-impl_to_bytes!(i16);
-impl_to_bytes!(u32);
-impl_to_bytes!(i32);
-impl_to_bytes!(u64);
-impl_to_bytes!(i64);
-
-impl_from_bytes!(u8);
-impl_from_bytes!(i8);
-impl_from_bytes!(u16);
-impl_from_bytes!(i16);
-impl_from_bytes!(u32);
-impl_from_bytes!(i32);
-impl_from_bytes!(u64);
-impl_from_bytes!(i64);
-
-newtype_int!(E8, u8);
-newtype_int!(E16, u16);
-newtype_int!(E32, u32);
-newtype_int!(E64, u64);
+pub use super::value::{Annotated, Dop2Number, Dop2Scalar, Generic, PhysicalValue};
+
+pub type AnnotatedU8 = Annotated<u8>;
+pub type AnnotatedU16 = Annotated<u16>;
+pub type AnnotatedI16 = Annotated<i16>;
+pub type AnnotatedI32 = Annotated<i32>;
+pub type AnnotatedU64 = Annotated<u64>;
+pub type AnnotatedBool = Annotated<bool>;
+pub type AnnotatedTimeStamp = Annotated<Dop2Timestamp>;
+
+pub type GenericU8 = Generic<u8>;
+pub type GenericU16 = Generic<u16>;
 
+pub use dop2core::types::{E8, E16, E32, E64};
@@ -0,0 +1,343 @@
+// Value structs that many leaves share, generic over the scalar they carry
+
+use core::fmt;
+use ordered_float::OrderedFloat;
+use crate::payloader::prelude::*;
+use crate::payloader::root::Dop2ParseTreeExpressible;
+#[cfg(feature = "chrono")]
+use super::types::Dop2TimestampUtc;
+use super::types::{E32, E64};
+use crate::payloader::units::Quantity;
+
+/// A Rust type held by one scalar `Dop2Payloads` kind
+pub trait Dop2Scalar: Sized + Clone {
+    const KIND: Dop2PayloadsKind;
+
+    /// The value of a payload of `KIND`, a type mismatch naming `type_name` for any other kind
+    fn from_payload(payload: Dop2Payloads, type_name: &'static str) -> Result<Self, Dop2Error>;
+
+    fn into_payload(self) -> Dop2Payloads;
+}
+
+/// A scalar that can be compared and stepped through, for the helpers of `Generic`
+pub trait Dop2Number: Dop2Scalar + Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+
+    /// `value` rounded to the nearest `Self`, `None` if it does not fit
+    fn from_f64(value: f64) -> Option<Self>;
+
+    /// `value` within `min..=max`, moved to the nearest `min + k * step` that does not pass `max`
+    fn snap(value: Self, min: Self, max: Self, step: Self) -> Self;
+}
+
+macro_rules! impl_dop2_scalar {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl Dop2Scalar for $ty {
+                const KIND: Dop2PayloadsKind = Dop2PayloadsKind::$variant;
+
+                fn from_payload(payload: Dop2Payloads, type_name: &'static str) -> Result<Self, Dop2Error> {
+                    match payload {
+                        Dop2Payloads::$variant(value) => Ok(value),
+                        other => Err(Dop2Error::type_mismatch(type_name, Self::KIND, Dop2PayloadsKind::from(&other))),
+                    }
+                }
+
+                fn into_payload(self) -> Dop2Payloads {
+                    Dop2Payloads::$variant(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_dop2_float {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl Dop2Scalar for $ty {
+                const KIND: Dop2PayloadsKind = Dop2PayloadsKind::$variant;
+
+                fn from_payload(payload: Dop2Payloads, type_name: &'static str) -> Result<Self, Dop2Error> {
+                    match payload {
+                        Dop2Payloads::$variant(value) => Ok(value.0),
+                        other => Err(Dop2Error::type_mismatch(type_name, Self::KIND, Dop2PayloadsKind::from(&other))),
+                    }
+                }
+
+                fn into_payload(self) -> Dop2Payloads {
+                    Dop2Payloads::$variant(OrderedFloat(self))
+                }
+            }
+
+            impl Dop2Number for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Option<Self> {
+                    value.is_finite().then_some(value as Self)
+                }
+
+                fn snap(value: Self, min: Self, max: Self, step: Self) -> Self {
+                    if min.is_nan() || max.is_nan() {
+                        return value;
+                    }
+                    let max = max.max(min);
+                    let value = value.clamp(min, max);
+                    if step.is_nan() || step <= 0.0 {
+                        return value;
+                    }
+                    let mut snapped = min + ((value - min) / step).round() * step;
+                    if snapped > max {
+                        snapped -= step;
+                    }
+                    snapped.max(min)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_dop2_integer {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Dop2Number for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Option<Self> {
+                    let rounded = value.round();
+                    let fits = rounded >= <$ty>::MIN as f64 && rounded <= <$ty>::MAX as f64;
+                    fits.then_some(rounded as Self)
+                }
+
+                fn snap(value: Self, min: Self, max: Self, step: Self) -> Self {
+                    let (min, max, step) = (min as i128, (max as i128).max(min as i128), step as i128);
+                    let value = (value as i128).clamp(min, max);
+                    if step <= 0 {
+                        return value as Self;
+                    }
+                    let mut snapped = min + (value - min + step / 2) / step * step;
+                    if snapped > max {
+                        snapped -= step;
+                    }
+                    // within min..=max, which both fit
+                    snapped.max(min) as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_dop2_scalar! {
+    bool => Boolean,
+    u8 => U8,
+    i8 => I8,
+    E8 => E8,
+    u16 => U16,
+    i16 => I16,
+    E16 => E16,
+    u32 => U32,
+    i32 => I32,
+    E32 => E32,
+    u64 => U64,
+    i64 => I64,
+    E64 => E64,
+}
+
+impl_dop2_float! {
+    f32 => F32,
+    f64 => F64,
+}
+
+impl_dop2_integer!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+#[cfg(feature = "chrono")]
+impl Dop2Scalar for Dop2TimestampUtc {
+    const KIND: Dop2PayloadsKind = Dop2PayloadsKind::U64;
+
+    fn from_payload(payload: Dop2Payloads, type_name: &'static str) -> Result<Self, Dop2Error> {
+        Dop2TimestampUtc::try_from(u64::from_payload(payload, type_name)?)
+    }
+
+    fn into_payload(self) -> Dop2Payloads {
+        Dop2Payloads::U64(self.into())
+    }
+}
+
+/// Reads field `number` of a value struct
+fn scalar_field<T: Dop2Scalar>(x: &Dop2Struct, number: u16, type_name: &'static str) -> Result<T, Dop2Error> {
+    match x.get_payload(number) {
+        Some(payload) => T::from_payload(payload, type_name).map_err(|e| e.in_struct(type_name)),
+        None => Err(Dop2Error::missing_field(type_name, T::KIND)),
+    }
+    .map_err(|e| e.within(Dop2PathSegment::Field(number)))
+}
+
+fn scalar_tagged<T: Dop2Scalar>(number: u16, value: T) -> TaggedDopField {
+    let value = value.into_payload();
+    TaggedDopField { field_index: number, tag: Dop2PayloadsKind::from(&value), value }
+}
+
+fn expect_struct(payload: Dop2Payloads, type_name: &'static str) -> Result<Dop2Struct, Dop2Error> {
+    match payload {
+        Dop2Payloads::MStruct(x) => Ok(x),
+        other => Err(Dop2Error::type_mismatch(type_name, Dop2PayloadsKind::MStruct, Dop2PayloadsKind::from(&other))),
+    }
+}
+
+/// A value with what it means, e.g. a temperature in hundredths of a degree Celsius
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotated<T> {
+    pub request_mask: u8,
+    pub value: T,
+    pub interpretation: ValueInterpretation,
+}
+
+/// A value scaled to the unit its `ValueInterpretation` names
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicalValue {
+    pub value: f64,
+    pub unit: PhysicalUnit,
+}
+
+impl fmt::Display for PhysicalValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+impl PhysicalValue {
+    /// A raw value scaled by its interpretation, if that names a physical unit
+    pub fn from_raw(raw: f64, interpretation: ValueInterpretation) -> Option<PhysicalValue> {
+        let (factor, unit) = interpretation.scale()?;
+        Some(PhysicalValue { value: raw * factor, unit })
+    }
+}
+
+impl<T: Dop2Number> Annotated<T> {
+    /// The value in the unit of its interpretation, if that is a physical one
+    pub fn physical_value(&self) -> Option<PhysicalValue> {
+        PhysicalValue::from_raw(self.value.to_f64(), self.interpretation)
+    }
+
+    /// The value as a temperature, duration, mass, .. if its interpretation names one;
+    /// the same scaling as `physical_value`, apart from timestamps
+    pub fn quantity(&self) -> Option<Quantity> {
+        Quantity::from_raw(self.value.to_f64(), self.interpretation)
+    }
+
+    /// Replaces the value with a quantity in the unit of the interpretation
+    pub fn set_quantity(&mut self, quantity: &Quantity) -> Result<(), Dop2Error> {
+        self.value = quantity.to_raw(self.interpretation).map_err(|e| e.in_struct("Annotated"))?;
+        Ok(())
+    }
+}
+
+impl<T: Dop2Scalar> Dop2ParseTreeExpressible for Annotated<T> {
+    fn from_parse_tree(payload: Dop2Payloads) -> Result<Self, Dop2Error> {
+        let x = expect_struct(payload, "Annotated")?;
+        Ok(Annotated {
+            request_mask: scalar_field(&x, 1, "Annotated")?,
+            value: scalar_field(&x, 2, "Annotated")?,
+            interpretation: scalar_field::<E8>(&x, 3, "Annotated")?.into(),
+        })
+    }
+}
+
+impl<T: Dop2Scalar> Dop2Fields for Annotated<T> {
+    const DOP2_FIELDS: &'static [Dop2FieldInfo] = &[
+        Dop2FieldInfo { index: 1, name: "request_mask", kind: Dop2PayloadsKind::U8, nested: None },
+        Dop2FieldInfo { index: 2, name: "value", kind: T::KIND, nested: None },
+        Dop2FieldInfo { index: 3, name: "interpretation", kind: Dop2PayloadsKind::E8, nested: None },
+    ];
+}
+
+impl<T: Dop2Scalar> TryFrom<Dop2Struct> for Annotated<T> {
+    type Error = Dop2Error;
+
+    fn try_from(value: Dop2Struct) -> Result<Self, Dop2Error> {
+        Annotated::from_parse_tree(Dop2Payloads::MStruct(value))
+    }
+}
+
+impl<T: Dop2Scalar> TryFrom<Annotated<T>> for Dop2Struct {
+    type Error = Dop2Error;
+
+    fn try_from(value: Annotated<T>) -> Result<Self, Dop2Error> {
+        let interpretation = E8::try_from(value.interpretation)
+            .map_err(|e| e.in_struct("Annotated").within(Dop2PathSegment::Field(3)))?;
+        Dop2Struct::from_fields(vec![
+            scalar_tagged(1, value.request_mask),
+            scalar_tagged(2, value.value),
+            scalar_tagged(3, interpretation),
+        ])
+    }
+}
+
+/// A setting with the range and step size the appliance accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generic<T> {
+    pub request_mask: u8,
+    pub min: T,
+    pub max: T,
+    pub current: T,
+    pub step_size: T,
+}
+
+impl<T: Dop2Number> Generic<T> {
+    pub fn is_in_range(&self, value: T) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// The closest value the appliance accepts
+    pub fn snap_to_step(&self, value: T) -> T {
+        T::snap(value, self.min, self.max, self.step_size)
+    }
+}
+
+impl<T: Dop2Scalar> Dop2ParseTreeExpressible for Generic<T> {
+    fn from_parse_tree(payload: Dop2Payloads) -> Result<Self, Dop2Error> {
+        let x = expect_struct(payload, "Generic")?;
+        Ok(Generic {
+            request_mask: scalar_field(&x, 1, "Generic")?,
+            min: scalar_field(&x, 2, "Generic")?,
+            max: scalar_field(&x, 3, "Generic")?,
+            current: scalar_field(&x, 4, "Generic")?,
+            step_size: scalar_field(&x, 5, "Generic")?,
+        })
+    }
+}
+
+impl<T: Dop2Scalar> Dop2Fields for Generic<T> {
+    const DOP2_FIELDS: &'static [Dop2FieldInfo] = &[
+        Dop2FieldInfo { index: 1, name: "request_mask", kind: Dop2PayloadsKind::U8, nested: None },
+        Dop2FieldInfo { index: 2, name: "min", kind: T::KIND, nested: None },
+        Dop2FieldInfo { index: 3, name: "max", kind: T::KIND, nested: None },
+        Dop2FieldInfo { index: 4, name: "current", kind: T::KIND, nested: None },
+        Dop2FieldInfo { index: 5, name: "step_size", kind: T::KIND, nested: None },
+    ];
+}
+
+impl<T: Dop2Scalar> TryFrom<Dop2Struct> for Generic<T> {
+    type Error = Dop2Error;
+
+    fn try_from(value: Dop2Struct) -> Result<Self, Dop2Error> {
+        Generic::from_parse_tree(Dop2Payloads::MStruct(value))
+    }
+}
+
+impl<T: Dop2Scalar> TryFrom<Generic<T>> for Dop2Struct {
+    type Error = Dop2Error;
+
+    fn try_from(value: Generic<T>) -> Result<Self, Dop2Error> {
+        Dop2Struct::from_fields(vec![
+            scalar_tagged(1, value.request_mask),
+            scalar_tagged(2, value.min),
+            scalar_tagged(3, value.max),
+            scalar_tagged(4, value.current),
+            scalar_tagged(5, value.step_size),
+        ])
+    }
+}
@@ -1,12 +1,17 @@
 pub mod prelude;
 
+pub mod codec;
 pub mod device;
+pub mod diff;
+pub mod dump;
 pub mod filesystem;
 pub mod comm_module;
 pub mod helper;
-pub mod parser;
-pub mod root;
 pub mod unit_ids;
+pub mod units;
 pub mod attribute_ids;
 pub mod meta;
 pub mod notifications;
+
+// the wire format itself lives in the no_std core crate
+pub use dop2core::{error, notation, parser, root};
@@ -1,14 +1,17 @@
-pub use dop2marshal::AssocTypes;
+pub use dop2marshal::{AssocTypes, Dop2Enum};
 pub use crate::impl_tryfrom_dop2struct;
-pub use crate::payloader::root::{Dop2Payloads, Dop2PayloadsKind, Dop2Struct, TaggedDopField};
+pub use crate::payloader::root::{Dop2Payloads, Dop2PayloadsKind, Dop2Struct, TaggedDopField, Dop2ExtraFields, Dop2FieldInfo, Dop2Fields, Dop2ParseTreeExpressible, RootNode};
+pub use crate::payloader::attribute_ids::HasAttributeIds;
 pub use crate::payloader::parser::DopArray;
+pub use crate::payloader::error::{Dop2Error, Dop2Path, Dop2PathSegment};
 pub use crate::payloader::helper::types::{E8, E16};
 pub use strum_macros::{EnumIter, EnumString};
 pub use num_enum::{TryFromPrimitive, IntoPrimitive};
 
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, EnumIter, EnumString, strum_macros::Display, IntoPrimitive)]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, strum_macros::Display, Dop2Enum)]
+#[dop2(wire = E8)]
 pub enum ValueInterpretation {
     None = 0,
     Percentage = 1,
@@ -126,7 +129,83 @@ pub enum ValueInterpretation {
     Quantity10Liters = 148,
     MicroSiemens10PerCm = 149,
     MicroSiemensPerCm = 150,
+    #[strum(to_string = "Unknown({0})")]
+    Unknown(u16),
 }
 
-crate::impl_tryfrom_wrapper!(ValueInterpretation, E8);
+impl ValueInterpretation {
+    /// Factor from the raw value to a physical unit, and that unit, for the interpretations that have one
+    pub fn scale(&self) -> Option<(f64, PhysicalUnit)> {
+        Some(match self {
+            Self::Percentage => (1.0, PhysicalUnit::Percent),
+            Self::Percent10 => (10.0, PhysicalUnit::Percent),
+            Self::Percent100 => (100.0, PhysicalUnit::Percent),
+            Self::Temperature10C => (10.0, PhysicalUnit::Celsius),
+            Self::Temperature10F => (10.0, PhysicalUnit::Fahrenheit),
+            Self::TemperatureC1 => (1.0, PhysicalUnit::Celsius),
+            Self::TemperatureC100 => (0.01, PhysicalUnit::Celsius),
+            Self::TemperatureF1 => (1.0, PhysicalUnit::Fahrenheit),
+            Self::TemperatureF100 => (0.01, PhysicalUnit::Fahrenheit),
+            Self::DurationSec | Self::DurationSecOrUndefined => (1.0, PhysicalUnit::Second),
+            Self::DurationMin => (60.0, PhysicalUnit::Second),
+            Self::TimeIn100Ms => (0.1, PhysicalUnit::Second),
+            Self::WeightGram => (1.0, PhysicalUnit::Gram),
+            Self::WeightTenthOfGram => (0.1, PhysicalUnit::Gram),
+            Self::Lbs => (1.0, PhysicalUnit::Pound),
+            Self::Lbs100 => (0.01, PhysicalUnit::Pound),
+            Self::EcoEnergyKwh => (1.0, PhysicalUnit::KilowattHour),
+            Self::EnergyWh => (1.0, PhysicalUnit::WattHour),
+            Self::EcoEnergyWatt => (1.0, PhysicalUnit::Watt),
+            Self::EcoWaterLiter | Self::LiquidQuantityLiters => (1.0, PhysicalUnit::Litre),
+            Self::Quantity10Liters => (10.0, PhysicalUnit::Litre),
+            Self::LiquidQuantityMilliliters | Self::RinseAidCapacityMl => (1.0, PhysicalUnit::Millilitre),
+            Self::LiquidQuantity100Milliliters => (100.0, PhysicalUnit::Millilitre),
+            Self::LiquidQuantity500Milliliters => (500.0, PhysicalUnit::Millilitre),
+            Self::FlowMlMin => (1.0, PhysicalUnit::MillilitrePerMinute),
+            Self::DrumSpeedRpm => (1.0, PhysicalUnit::Rpm),
+            Self::DrumSpeed10Rpm => (10.0, PhysicalUnit::Rpm),
+            Self::WaterLevelMmws => (1.0, PhysicalUnit::MillimetreWaterColumn),
+            Self::WaterHardnessDh => (1.0, PhysicalUnit::GermanHardness),
+            Self::MicroSiemensPerCm => (1.0, PhysicalUnit::MicroSiemensPerCm),
+            Self::MicroSiemens10PerCm => (10.0, PhysicalUnit::MicroSiemensPerCm),
+            _ => return None,
+        })
+    }
+}
 
+/// The physical units of `ValueInterpretation::scale`, shown as their symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+pub enum PhysicalUnit {
+    #[strum(to_string = "%")]
+    Percent,
+    #[strum(to_string = "°C")]
+    Celsius,
+    #[strum(to_string = "°F")]
+    Fahrenheit,
+    #[strum(to_string = "s")]
+    Second,
+    #[strum(to_string = "g")]
+    Gram,
+    #[strum(to_string = "lb")]
+    Pound,
+    #[strum(to_string = "kWh")]
+    KilowattHour,
+    #[strum(to_string = "Wh")]
+    WattHour,
+    #[strum(to_string = "W")]
+    Watt,
+    #[strum(to_string = "l")]
+    Litre,
+    #[strum(to_string = "ml")]
+    Millilitre,
+    #[strum(to_string = "ml/min")]
+    MillilitrePerMinute,
+    #[strum(to_string = "rpm")]
+    Rpm,
+    #[strum(to_string = "mmWS")]
+    MillimetreWaterColumn,
+    #[strum(to_string = "°dH")]
+    GermanHardness,
+    #[strum(to_string = "µS/cm")]
+    MicroSiemensPerCm,
+}
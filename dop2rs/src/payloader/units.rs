@@ -0,0 +1,333 @@
+// Physical quantities behind the numbers that carry a ValueInterpretation
+
+use std::any::Any;
+use std::time::Duration;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+use crate::payloader::prelude::*;
+use crate::payloader::helper::types::{Annotated, Dop2Number, PhysicalValue};
+use crate::payloader::device::generic::settings_value::SfValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Temperature {
+    celsius: f64,
+}
+
+impl Temperature {
+    pub fn from_celsius(celsius: f64) -> Self {
+        Temperature { celsius }
+    }
+
+    pub fn from_fahrenheit(fahrenheit: f64) -> Self {
+        Temperature { celsius: (fahrenheit - 32.0) * 5.0 / 9.0 }
+    }
+
+    pub fn celsius(&self) -> f64 {
+        self.celsius
+    }
+
+    pub fn fahrenheit(&self) -> f64 {
+        self.celsius * 9.0 / 5.0 + 32.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Mass {
+    grams: f64,
+}
+
+impl Mass {
+    const GRAMS_PER_POUND: f64 = 453.592_37;
+
+    pub fn from_grams(grams: f64) -> Self {
+        Mass { grams }
+    }
+
+    pub fn from_pounds(pounds: f64) -> Self {
+        Mass { grams: pounds * Self::GRAMS_PER_POUND }
+    }
+
+    pub fn grams(&self) -> f64 {
+        self.grams
+    }
+
+    pub fn pounds(&self) -> f64 {
+        self.grams / Self::GRAMS_PER_POUND
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Energy {
+    watt_hours: f64,
+}
+
+impl Energy {
+    pub fn from_watt_hours(watt_hours: f64) -> Self {
+        Energy { watt_hours }
+    }
+
+    pub fn from_kilowatt_hours(kilowatt_hours: f64) -> Self {
+        Energy { watt_hours: kilowatt_hours * 1000.0 }
+    }
+
+    pub fn watt_hours(&self) -> f64 {
+        self.watt_hours
+    }
+
+    pub fn kilowatt_hours(&self) -> f64 {
+        self.watt_hours / 1000.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Volume {
+    millilitres: f64,
+}
+
+impl Volume {
+    pub fn from_millilitres(millilitres: f64) -> Self {
+        Volume { millilitres }
+    }
+
+    pub fn from_litres(litres: f64) -> Self {
+        Volume { millilitres: litres * 1000.0 }
+    }
+
+    pub fn millilitres(&self) -> f64 {
+        self.millilitres
+    }
+
+    pub fn litres(&self) -> f64 {
+        self.millilitres / 1000.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct RotationSpeed {
+    rpm: f64,
+}
+
+impl RotationSpeed {
+    pub fn from_rpm(rpm: f64) -> Self {
+        RotationSpeed { rpm }
+    }
+
+    pub fn rpm(&self) -> f64 {
+        self.rpm
+    }
+}
+
+/// What a raw value means once its `ValueInterpretation` is applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    Temperature(Temperature),
+    Duration(Duration),
+    Mass(Mass),
+    Energy(Energy),
+    Volume(Volume),
+    RotationSpeed(RotationSpeed),
+    #[cfg(feature = "chrono")]
+    Timestamp(DateTime<Utc>),
+}
+
+impl Quantity {
+    /// The quantity of a raw value, if its interpretation is one of the quantities above
+    pub fn from_raw(raw: f64, interpretation: ValueInterpretation) -> Option<Quantity> {
+        if is_timestamp(interpretation) {
+            return Quantity::timestamp(raw);
+        }
+        Quantity::from_physical(PhysicalValue::from_raw(raw, interpretation)?)
+    }
+
+    /// The quantity a physical value measures, if it is one of the quantities above
+    pub fn from_physical(physical: PhysicalValue) -> Option<Quantity> {
+        let value = physical.value;
+        Some(match physical.unit {
+            PhysicalUnit::Celsius => Quantity::Temperature(Temperature::from_celsius(value)),
+            PhysicalUnit::Fahrenheit => Quantity::Temperature(Temperature::from_fahrenheit(value)),
+            PhysicalUnit::Second => Quantity::Duration(Duration::try_from_secs_f64(value).ok()?),
+            PhysicalUnit::Gram => Quantity::Mass(Mass::from_grams(value)),
+            PhysicalUnit::Pound => Quantity::Mass(Mass::from_pounds(value)),
+            PhysicalUnit::WattHour => Quantity::Energy(Energy::from_watt_hours(value)),
+            PhysicalUnit::KilowattHour => Quantity::Energy(Energy::from_kilowatt_hours(value)),
+            PhysicalUnit::Millilitre => Quantity::Volume(Volume::from_millilitres(value)),
+            PhysicalUnit::Litre => Quantity::Volume(Volume::from_litres(value)),
+            PhysicalUnit::Rpm => Quantity::RotationSpeed(RotationSpeed::from_rpm(value)),
+            _ => return None,
+        })
+    }
+
+    /// The raw value for a field with the given interpretation, rounded to what `T` holds
+    pub fn to_raw<T: Dop2Number>(&self, interpretation: ValueInterpretation) -> Result<T, Dop2Error> {
+        let raw = if is_timestamp(interpretation) {
+            self.timestamp_seconds()
+        } else {
+            interpretation
+                .scale()
+                .and_then(|(factor, unit)| self.value_in(unit).map(|value| value / factor))
+        };
+        raw.and_then(T::from_f64).ok_or_else(|| {
+            Dop2Error::invalid_conversion(std::any::type_name::<T>(), format!("{} as {}", self.describe(interpretation), interpretation))
+        })
+    }
+
+    /// The quantity in `unit`, if that measures the same thing
+    pub fn in_unit(&self, unit: PhysicalUnit) -> Option<PhysicalValue> {
+        self.value_in(unit).map(|value| PhysicalValue { value, unit })
+    }
+
+    /// The quantity in the unit of `interpretation` for messages, as `Debug` if it has none
+    pub(crate) fn describe(&self, interpretation: ValueInterpretation) -> String {
+        match interpretation.scale().and_then(|(_, unit)| self.in_unit(unit)) {
+            Some(physical) => physical.to_string(),
+            None => format!("{:?}", self),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    fn timestamp(seconds: f64) -> Option<Quantity> {
+        DateTime::from_timestamp(seconds as i64, 0).map(Quantity::Timestamp)
+    }
+
+    /// Timestamps are only quantities with the `chrono` feature
+    #[cfg(not(feature = "chrono"))]
+    fn timestamp(_seconds: f64) -> Option<Quantity> {
+        None
+    }
+
+    #[cfg(feature = "chrono")]
+    fn timestamp_seconds(&self) -> Option<f64> {
+        match self {
+            Quantity::Timestamp(time) => Some(time.timestamp() as f64),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    fn timestamp_seconds(&self) -> Option<f64> {
+        None
+    }
+
+    /// The value in one of the units of `ValueInterpretation::scale`
+    fn value_in(&self, unit: PhysicalUnit) -> Option<f64> {
+        match (self, unit) {
+            (Quantity::Temperature(t), PhysicalUnit::Celsius) => Some(t.celsius()),
+            (Quantity::Temperature(t), PhysicalUnit::Fahrenheit) => Some(t.fahrenheit()),
+            (Quantity::Duration(d), PhysicalUnit::Second) => Some(d.as_secs_f64()),
+            (Quantity::Mass(m), PhysicalUnit::Gram) => Some(m.grams()),
+            (Quantity::Mass(m), PhysicalUnit::Pound) => Some(m.pounds()),
+            (Quantity::Energy(e), PhysicalUnit::WattHour) => Some(e.watt_hours()),
+            (Quantity::Energy(e), PhysicalUnit::KilowattHour) => Some(e.kilowatt_hours()),
+            (Quantity::Volume(v), PhysicalUnit::Millilitre) => Some(v.millilitres()),
+            (Quantity::Volume(v), PhysicalUnit::Litre) => Some(v.litres()),
+            (Quantity::RotationSpeed(r), PhysicalUnit::Rpm) => Some(r.rpm()),
+            _ => None,
+        }
+    }
+}
+
+/// Every `Annotated` field of a root type whose interpretation names a quantity, by field name
+pub fn annotated_quantities<T: HasAttributeIds + Dop2Fields>(value: &T) -> Vec<(&'static str, Quantity)> {
+    T::DOP2_FIELDS
+        .iter()
+        .filter_map(|info| Some((info.name, annotated_quantity(value.field_value(info.index)?)?)))
+        .collect()
+}
+
+/// The quantity of a field that is an `Annotated` of any number type
+fn annotated_quantity(field: &dyn Any) -> Option<Quantity> {
+    macro_rules! annotated {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(annotated) = field.downcast_ref::<Annotated<$ty>>() {
+                    return annotated.quantity();
+                }
+            )*
+        };
+    }
+    annotated!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+    None
+}
+
+fn is_timestamp(interpretation: ValueInterpretation) -> bool {
+    matches!(interpretation, ValueInterpretation::TimeUtc | ValueInterpretation::TimeUtc0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightUnit {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// The units a device shows temperatures and weights in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UnitPreferences {
+    pub temperature: TemperatureUnit,
+    pub weight: WeightUnit,
+}
+
+impl UnitPreferences {
+    /// From the settings interpreted as `TemperatureUnit` and `WeightUnit`, where 0 is
+    /// the metric unit and any other value the imperial one
+    pub fn from_settings<'a>(settings: impl IntoIterator<Item = &'a SfValue>) -> Self {
+        let mut preferences = UnitPreferences::default();
+        for setting in settings {
+            let imperial = *setting.current_value() != 0;
+            match setting.value_interpretation() {
+                ValueInterpretation::TemperatureUnit => {
+                    preferences.temperature = if imperial { TemperatureUnit::Fahrenheit } else { TemperatureUnit::Celsius };
+                }
+                ValueInterpretation::WeightUnit => {
+                    preferences.weight = if imperial { WeightUnit::Imperial } else { WeightUnit::Metric };
+                }
+                _ => {}
+            }
+        }
+        preferences
+    }
+
+    /// The quantity in the unit the device shows it in, `None` for a timestamp
+    pub fn show(&self, quantity: &Quantity) -> Option<PhysicalValue> {
+        let (value, unit) = match quantity {
+            Quantity::Temperature(t) => match self.temperature {
+                TemperatureUnit::Celsius => (t.celsius(), PhysicalUnit::Celsius),
+                TemperatureUnit::Fahrenheit => (t.fahrenheit(), PhysicalUnit::Fahrenheit),
+            },
+            Quantity::Mass(m) => match self.weight {
+                WeightUnit::Metric => (m.grams(), PhysicalUnit::Gram),
+                WeightUnit::Imperial => (m.pounds(), PhysicalUnit::Pound),
+            },
+            Quantity::Duration(d) => (d.as_secs_f64(), PhysicalUnit::Second),
+            Quantity::Energy(e) => (e.kilowatt_hours(), PhysicalUnit::KilowattHour),
+            Quantity::Volume(v) => (v.litres(), PhysicalUnit::Litre),
+            Quantity::RotationSpeed(r) => (r.rpm(), PhysicalUnit::Rpm),
+            #[cfg(feature = "chrono")]
+            Quantity::Timestamp(_) => return None,
+        };
+        Some(PhysicalValue { value, unit })
+    }
+
+    /// A temperature entered in the unit the device shows
+    pub fn temperature(&self, value: f64) -> Temperature {
+        match self.temperature {
+            TemperatureUnit::Celsius => Temperature::from_celsius(value),
+            TemperatureUnit::Fahrenheit => Temperature::from_fahrenheit(value),
+        }
+    }
+
+    /// A weight entered in the unit the device shows
+    pub fn mass(&self, value: f64) -> Mass {
+        match self.weight {
+            WeightUnit::Metric => Mass::from_grams(value),
+            WeightUnit::Imperial => Mass::from_pounds(value),
+        }
+    }
+}
@@ -3,62 +3,81 @@ use crate::*;
 use crate::payloader::device::generic::state::cs_context::{CSContext, CSContextParametersWasher};
 use crate::payloader::helper::types::{AnnotatedBool, GenericU8, GenericU16};
 use crate::payloader::device::generic::program_selection::enums::ProgramIdOven;
-use crate::payloader::prelude::ValueInterpretation;
-// Static test data structure with individual fields
-struct TestPayloads {
-    oven_14_130: &'static str,
-    oven_2_1586: &'static str,
-    oven_9_19: &'static str,
-    oven_ident: &'static str,
-    oven_2_114: &'static str,
-    oven_1_391: &'static str,
-    oven_1_209: &'static str,
-    oven_2_1585: &'static str,
-    oven_1_1599: &'static str,
-    washer_1_154: &'static str,
-    synthetic_garbage_missing_field: &'static str,
-    synthetic_garbage_invalid_boolean: &'static str,
-}
-
-static TEST_PAYLOADS: TestPayloads = TestPayloads {
-    // actual oven payloads
-    oven_14_130:"000e000e008200010001000100010400", // one E8, no padding
-    oven_2_1586: "0016000206320000000000030001040400020405000304012020202020202020", // devicecombistate, 3 E8, padding
-    oven_9_19: "00230009001300000001000500010500ab0002050001000305fb00000405fb0000050500002020202020202020202020", // U16s with padding
-//001c000e007a00010001000200010b0000000068e814fd000209000000002020 //Unsigned64
-    oven_ident: "004e000e061d0001000100080002040000030400000412000530392e31340005051a390006120008001d63fffeaf152f0007040000081200080000000000000000000914000a00000000000000000000",
-    oven_2_114: "009f0002007200000000000200010226000217004603f903f303fa03ee03f1001e0021001b001c001d001a000a000b000c00150018001903e800030005000400160011000e0012042e042d04590464045a046b046904520453046504560457046c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000202020202020202020202020202020",
-    oven_1_391: "02c1000101870000000000090001100003000104000002040000030400000710001a0001070000000207000000031000030001020000020500000003040000041000030001020000020500000003040000051000030001020000020500000003040000061000030001020000020500000003040000071000030001020000020500000003040000081000030001020000020200000304000009100003000102000002020000030400000a0400000b0200000c100003000102000002020000030400000d100003000102000002020000030400000e10000300010200000202000003040000100400001210000300010200000205000000030400001308000000000014070000001510000300010200000205000000030400001610000300010200000202000003040000171000030001020000020500000003040000181000030001020000020200000304000019100003000102000002020000030400001a1000030001020000020b000000000000000000030400001c070000001d1000030001020000020b000000000000000000030400000810001e000208000000000003080000000000040800000000000508000000000006080000000000070400000804000009100003000104000002040000030400000b0400000c050000000d0800000000000e0800000000000f0200001004000011040000120100001301000014010000150100001608000000000017050000001805000000190200001a0100001b0100001c0100001d050000001e050000001f100003000101000002010000030100002010000400010100000201000003010000040100000917000e00000000000000000000000000000000000000000000000000000000000a0400000b0100000c070000000d0100001110000700010100000208000000000003080000000000040800000000000512000c00000000000000000000000000060500000007080000000020202020202020202020202020", // struct with U8s
-    oven_1_209: "0050000100d1000000000002000121000200020001010000020b000000000000000000020001010000020b000000000000000000022100020002000104000002090000015c000200010401000209000000002020202020202020202020202020", // Struct[]
-    oven_2_1585: "02c1000106310000000000090001100003000104040002040600030404000710001a00010727100002070000000310000300010200000205ffff00030400000410000300010208000205000000030405000510000300010200000205ffff00030405000610000300010208000205086300030403000710000300010200000205ffff0003040300081000030001020800020201000304000009100003000102000002020000030400000a0432000b0200000c100003000102000002020000030400000d100003000102000002020000030400000e100003000102000002020000030400001004000012100003000102000002050000000304000013080000000000140700000015100003000102080002053e8000030403001610000300010200000202000003040100171000030001020000020500000003040300181000030001020000020200000304000019100003000102000002020000030407001a1000030001020800020b0000000068e814f40003041f001c07000d001d1000030001020000020b000000000000000000030400000810001e000208000000000003080000000000040800000000000508000000000006080000000000070400000804000009100003000104000002040000030400000b0400000c05ffff000d0800000000000e0800000000000f02ff001004000011040000120100001301000014010000150100001608000001630017050000001805000000190200001a0100001b0100001c0100001d050000001e050000001f100003000101000002010000030100002010000400010100000201000003010000040100000917000e0013000e000200380070000000000000000000000000000000000000000a0401000b0101000c070000000d0100001110000700010100000208ffffffff00030800000000000408ffffffff000512000c0000000000000000000000000006050000000708ffffffff20202020202020202020202020",
-    oven_1_1599: "026c0001063f00000000000620001210008000400010500010002050060000308000000010004180004518036610007800000000000000000000004000105006100020500960003080000000200041800040000000000000000000000000000000000040001053a980002053ab7000308000001000004180004000000010000000000000000000000000004000105010300020501420003080000001000041800045e40d8110000000000000000000000000004000105014300020501620003080000002000041800040000301f00000000000000000000000000040001053e800002053edf0003080000020000041800042bdfdf0e0000003f0000000000000000000400010500000002050000000308000000000004180004000000000000000000000000000000000004000105000000020500000003080000000000041800040000000000000000000000000000000000022100010004000105016300020507e0000308000000400004180040f99c76ae11f8c681c103071b0001643cefffe308513ffcbb000007fe0000000003807c00000000000ff0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002020", // programGroupsComplete
-    washer_1_154: "00d40002009a0000000000020001070000000210000800011000030001020900020100000304000002100006000102000002050000000305000000040500000005050000000604000003100006000102000002020000030200000402000005020000060400000410000600010200000205000000030500000004050000000505000000060400000510000600010200000202000003020000040200000502000006040000061000030001020000020100000304000007100003000102000002010000030400000810000300010200000201000003040020202020202020202020",
-    synthetic_garbage_missing_field: "0016000206320000000000030001040400020405000304012020202020202020",
-    synthetic_garbage_invalid_boolean: "000e000e0082000100010001000101FF"
-};
+use crate::payloader::device::generic::state::combined::ProcessState;
+use crate::payloader::prelude::{PhysicalUnit, ValueInterpretation};
+use crate::payloader::root::RootNode;
+use crate::payloader::error::{Dop2Error, Dop2PathSegment};
+use crate::fixtures::TEST_PAYLOADS;
+use ordered_float::OrderedFloat;
+use crate::payloader::codec::{Dop2Codec, Dop2CodecError};
+use bytes::{BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use crate::attribute_registry::AttributeRegistry;
+use crate::payloader::dump::annotated_dump;
+use crate::payloader::diff::{Dop2ChangeKind, Dop2Diff, Dop2DiffError};
+use crate::payloader::root::DopPadding;
+use proptest::prelude::*;
  static TEST_BANK : [&str; 7] = [TEST_PAYLOADS.oven_14_130, TEST_PAYLOADS.oven_2_1586, TEST_PAYLOADS.oven_9_19, TEST_PAYLOADS.oven_ident, TEST_PAYLOADS.oven_2_114, TEST_PAYLOADS.oven_1_391, TEST_PAYLOADS.oven_1_209];
 #[test]
  fn test_synthetic_garbage_invalid_boolean() {
-    let mut parser = Dop2Parser::new(hex::decode(TEST_PAYLOADS.synthetic_garbage_invalid_boolean).unwrap());
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_invalid_boolean).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     assert!(result.is_err());
  }
 #[test]
  fn test_synthetic_garbage_missing_field() {
-    let mut parser = Dop2Parser::new(hex::decode(TEST_PAYLOADS.synthetic_garbage_missing_field).unwrap());
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_missing_field).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     let root_node = result.unwrap();
     let context = CSContext::try_from(root_node.root_struct.clone());
     assert!(context.is_err());
  }
+#[test]
+fn test_parse_error_reports_offset_and_path() {
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_invalid_boolean).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
+    let error = RootNode::parse(&mut parser).unwrap_err();
+    assert!(matches!(error, Dop2Error::InvalidValue { kind: Dop2PayloadsKind::Boolean, .. }));
+    assert_eq!(error.offset(), Some(15));
+    assert_eq!(error.path().to_string(), "unit 14 / attr 130 / field 1");
+}
+
+#[test]
+fn test_parse_error_path_through_struct_array() {
+    // first boolean of the first AStruct element replaced by 0x02
+    let mut bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    bytes[22] = 0x02;
+    let mut parser = Dop2Parser::new(&bytes);
+    let error = RootNode::parse(&mut parser).unwrap_err();
+    assert_eq!(error.offset(), Some(22));
+    assert_eq!(error.path().to_string(), "unit 1 / attr 209 / field 1 / element 0 / field 1");
+}
+
+#[test]
+fn test_conversion_error_reports_type_and_kinds() {
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_missing_field).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    let error = CSContext::try_from(root_node.root_struct).unwrap_err();
+    assert_eq!(error, Dop2Error::TypeMismatch {
+        type_name: "CSContext",
+        expected: Dop2PayloadsKind::E16,
+        found: Dop2PayloadsKind::E8,
+        path: Dop2PathSegment::Field(1).into(),
+    });
+    assert_eq!(error.to_string(), "field 1: expected E16 but found E8 while building CSContext");
+}
+
 #[test]
 fn test_root_node_parse_insufficient_data() {
     // Test with insufficient data (only 2 bytes, need at least 4)
     let test_data = vec![0x12, 0x34];
     
-    let mut parser = Dop2Parser::new(test_data);
+    let mut parser = Dop2Parser::new(&test_data);
     let result = RootNode::parse(&mut parser);
-    assert!(result.is_err());
+    assert!(matches!(result, Err(Dop2Error::UnexpectedEof { offset: 2, needed: 2, available: 0, .. })));
 }
 
 #[test]
@@ -71,10 +90,828 @@ fn round_trips ()
     }
 }
 
+#[test]
+fn test_float_wire_format() {
+    // F32 1.5 in field 1, F64 -2.0 in field 2
+    let frame = "001c0002000100000000000200010e3fc0000000020fc0000000000000002020";
+    let bytes = hex::decode(frame).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    assert_eq!(root_node.root_struct.get_payload(1), Some(Dop2Payloads::F32(OrderedFloat(1.5))));
+    assert_eq!(root_node.root_struct.get_payload(2), Some(Dop2Payloads::F64(OrderedFloat(-2.0))));
+    test_round_trip(frame);
+}
+
+#[test]
+fn test_float_and_wide_array_round_trips() {
+    let fields = vec![
+        TaggedDopField::from_payload(1, Dop2Payloads::F32(OrderedFloat(21.5))),
+        TaggedDopField::from_payload(2, Dop2Payloads::F64(OrderedFloat(-0.125))),
+        TaggedDopField::from_payload(3, Dop2Payloads::F32(OrderedFloat(f32::NAN))),
+        TaggedDopField::from_payload(4, Dop2Payloads::ArrayBool(DopArray { count: 3, elements: vec![true, false, true] })),
+        TaggedDopField::from_payload(5, Dop2Payloads::ArrayI64(DopArray { count: 4, elements: vec![i64::MIN, -1, 0, i64::MAX] })),
+        TaggedDopField::from_payload(6, Dop2Payloads::ArrayF32(DopArray {
+            count: 3,
+            elements: vec![OrderedFloat(0.0), OrderedFloat(-1.5), OrderedFloat(f32::INFINITY)],
+        })),
+        TaggedDopField::from_payload(7, Dop2Payloads::ArrayF64(DopArray {
+            count: 2,
+            elements: vec![OrderedFloat(f64::MIN_POSITIVE), OrderedFloat(1e300)],
+        })),
+    ];
+    let root_node = RootNode::single(2, 1585, Dop2Struct::from_fields(fields.clone()).unwrap());
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+
+    let mut parser = Dop2Parser::new(&data);
+    let parsed = RootNode::parse(&mut parser).unwrap();
+    assert_eq!(parsed.root_struct.fields, fields);
+    test_round_trip(&hex::encode(&data));
+}
+
+#[test]
+fn test_lenient_parse_keeps_unknown_tag_as_raw() {
+    // field 2 is a struct whose second field carries the unknown tag 0x63
+    let frame = "001d000206310000000000020001050007000210000200010403000263abcd20".to_owned();
+    let bytes = hex::decode(&frame).unwrap();
+
+    let mut strict = Dop2Parser::new(&bytes);
+    assert!(matches!(RootNode::parse(&mut strict), Err(Dop2Error::UnknownTag { tag: 0x63, offset: 28, .. })));
+
+    let mut parser = Dop2Parser::lenient(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    let warnings = parser.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].raw_bytes, 5);
+    assert_eq!(warnings[0].error.path().to_string(), "unit 2 / attr 1585 / field 2 / field 2");
+
+    let Some(Dop2Payloads::MStruct(inner)) = root_node.root_struct.get_payload(2) else {
+        panic!("field 2 should still be a struct");
+    };
+    assert_eq!(inner.get_payload(1), Some(Dop2Payloads::E8(E8(3))));
+    assert_eq!(inner.get_payload(2), Some(Dop2Payloads::Raw(hex::decode("000263abcd").unwrap())));
+
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data), frame);
+}
+
+#[test]
+fn test_lenient_parse_keeps_trailing_garbage() {
+    let frame = TEST_PAYLOADS.oven_14_130.to_owned() + "deadbeef";
+    let bytes = hex::decode(&frame).unwrap();
+
+    let mut strict = Dop2Parser::new(&bytes);
+    assert!(matches!(RootNode::parse(&mut strict), Err(Dop2Error::TrailingGarbage { byte: 0xde, .. })));
+
+    let mut parser = Dop2Parser::lenient(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    assert_eq!(parser.warnings().len(), 1);
+    assert!(matches!(parser.warnings()[0].error, Dop2Error::TrailingGarbage { byte: 0xde, .. }));
+    assert_eq!(parser.warnings()[0].error.path().to_string(), "unit 14 / attr 130");
+
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data), frame);
+}
+
+#[test]
+fn test_lenient_parse_of_clean_frame_has_no_warnings() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap();
+    let mut parser = Dop2Parser::lenient(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    assert!(parser.warnings().is_empty());
+    assert!(root_node.trailing.is_empty());
+}
+
+/// Every captured frame, labelled with where it came from
+fn corpus() -> Vec<(String, String)> {
+    let mut frames: Vec<(String, String)> = TEST_PAYLOADS
+        .all()
+        .iter()
+        .map(|(name, hex)| (format!("fixtures::{}", name), hex.to_string()))
+        .collect();
+
+    for (file, json) in [
+        ("washer.json", include_str!("../tests/washer.json")),
+        ("oven.json", include_str!("../tests/oven.json")),
+    ] {
+        let captures: serde_json::Value = serde_json::from_str(json).unwrap();
+        for (unit, attributes) in captures.as_object().unwrap() {
+            for (attribute, frame) in attributes.as_object().unwrap() {
+                // values are Python byte literals of the hex dump, b'...'
+                let hex = frame.as_str().unwrap().trim_start_matches("b'").trim_end_matches('\'');
+                frames.push((format!("{} {}/{}", file, unit, attribute), hex.to_string()));
+            }
+        }
+    }
+
+    let lines = include_str!("../tests/washer_lines.txt");
+    for (number, line) in lines.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        frames.push((format!("washer_lines.txt:{}", number + 1), line.trim().to_string()));
+    }
+    frames
+}
+
+#[test]
+fn test_corpus_round_trips_byte_exact() {
+    let frames = corpus();
+    assert!(frames.len() > 200);
+    for (name, frame) in frames {
+        let bytes = hex::decode(&frame).unwrap();
+        // frames the strict parser rejects must still come back unchanged from a lenient parse
+        let mut parser = Dop2Parser::new(&bytes);
+        let root_node = match RootNode::parse(&mut parser) {
+            Ok(root_node) => root_node,
+            Err(_) => RootNode::parse(&mut Dop2Parser::lenient(&bytes)).unwrap(),
+        };
+        let mut data: Vec<u8> = Vec::new();
+        root_node.to_bytes(&mut data).unwrap();
+        assert_eq!(hex::encode(&data), hex::encode(&bytes), "{} did not round trip", name);
+    }
+}
+
+#[test]
+fn test_round_trip_keeps_declared_counts() {
+    // two fields numbered 1 and 4, behind a length word that claims one byte too many
+    let frame = "00130002063200000000000200010404000404052020202020202020202020";
+    let bytes = hex::decode(frame).unwrap();
+    let error = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LengthMismatch { declared: 0x13, actual: 0x12, .. }));
+    assert_eq!(error.path().to_string(), "unit 2 / attr 1586");
+    let mut parser = Dop2Parser::lenient(&bytes);
+    let root_node = RootNode::parse(&mut parser).unwrap();
+    assert!(matches!(parser.warnings()[0].error, Dop2Error::LengthMismatch { declared: 0x13, actual: 0x12, .. }));
+    assert_eq!(root_node.root_struct.declared_fields, 2);
+    assert_eq!(Dop2Struct::from_fields(root_node.root_struct.fields.clone()).unwrap().declared_fields, 2);
+
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data), frame);
+}
+
+#[test]
+fn test_string_round_trip_includes_length() {
+    let fields = vec![TaggedDopField::from_payload(1, Dop2Payloads::MString("09.14\u{e9}".to_string()))];
+    let root_node = RootNode::single(14, 1565, Dop2Struct::from_fields(fields.clone()).unwrap());
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data[10..]), "0001000120000630392e3134e9202020202020202020");
+
+    let parsed = RootNode::parse(&mut Dop2Parser::new(&data)).unwrap();
+    assert_eq!(parsed.root_struct.fields, fields);
+}
+
+#[test]
+fn test_strings_the_wire_cannot_hold_fail_to_encode() {
+    let inner = Dop2Struct::from_fields(vec![TaggedDopField::from_payload(2, Dop2Payloads::MString("20 €".to_string()))]).unwrap();
+    let root_node = RootNode::single(14, 1565, Dop2Struct::from_fields(vec![TaggedDopField::from_payload(7, Dop2Payloads::MStruct(inner))]).unwrap());
+    let error = root_node.to_bytes(&mut Vec::new()).unwrap_err();
+    assert!(matches!(error, Dop2Error::InvalidConversion { type_name: "MString", .. }));
+    assert_eq!(error.path().to_string(), "unit 14 / attr 1565 / field 7 / field 2");
+
+    let long = Dop2Payloads::MString("x".repeat(usize::from(u16::MAX) + 1));
+    let root_node = RootNode::single(14, 1565, Dop2Struct::from_fields(vec![TaggedDopField::from_payload(1, long)]).unwrap());
+    assert!(matches!(root_node.to_bytes(&mut Vec::new()), Err(Dop2Error::InvalidConversion { type_name: "MString", .. })));
+}
+
+#[test]
+fn test_structs_the_count_word_cannot_hold_are_errors() {
+    let fields: Vec<TaggedDopField> = (0..=u16::MAX).map(|index| TaggedDopField::from_payload(index, Dop2Payloads::Boolean(true))).collect();
+    assert!(matches!(Dop2Struct::from_fields(fields), Err(Dop2Error::LimitExceeded { value: 65536, max: 65535, .. })));
+
+    let json = format!("{{{}}}", (0..=u16::MAX).map(|index| format!("\"{}\": {{\"Boolean\": true}}", index)).collect::<Vec<_>>().join(","));
+    assert!(serde_json::from_str::<Dop2Struct>(&json).is_err());
+
+    let mut full = RootNode::single(14, 1565, Dop2Struct::from_fields(vec![TaggedDopField::from_payload(1, Dop2Payloads::U8(1))]).unwrap());
+    full.root_struct.declared_fields = u16::MAX;
+    assert!(matches!(full.set("2", Dop2Payloads::U8(2)), Err(Dop2Error::LimitExceeded { .. })));
+    assert_eq!(full.get("2"), None);
+}
+
+#[test]
+fn test_codec_splits_concatenated_frames() {
+    let body = TEST_PAYLOADS.oven_14_130.to_owned() + TEST_PAYLOADS.oven_2_1586 + TEST_PAYLOADS.oven_1_209;
+    let bytes = hex::decode(&body).unwrap();
+    let frames = Dop2Codec::new().decode_body(&bytes).unwrap();
+    assert_eq!(
+        frames.iter().map(|f| (f.unit, f.attribute)).collect::<Vec<_>>(),
+        vec![(14, 130), (2, 1586), (1, 209)]
+    );
+
+    let mut encoded = BytesMut::new();
+    let mut codec = Dop2Codec::new();
+    for frame in frames {
+        codec.encode(frame, &mut encoded).unwrap();
+    }
+    assert_eq!(hex::encode(&encoded), body);
+}
+
+#[test]
+fn test_codec_waits_for_partial_frames() {
+    let body = TEST_PAYLOADS.oven_9_19.to_owned() + TEST_PAYLOADS.oven_2_1586;
+    let bytes = hex::decode(&body).unwrap();
+    let mut codec = Dop2Codec::new();
+    let mut buffer = BytesMut::new();
+    let mut frames = Vec::new();
+    for byte in bytes {
+        buffer.put_u8(byte);
+        if let Some(frame) = codec.decode(&mut buffer).unwrap() {
+            frames.push(frame);
+        }
+    }
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].attribute, 19);
+    assert_eq!(frames[1].attribute, 1586);
+    assert!(buffer.is_empty());
+    assert!(codec.decode_eof(&mut buffer).unwrap().is_none());
+}
+
+#[test]
+fn test_codec_rejects_oversized_and_truncated_frames() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_391).unwrap();
+    let mut codec = Dop2Codec::with_max_frame_size(256);
+    let mut buffer = BytesMut::from(&bytes[..2]);
+    assert!(matches!(
+        codec.decode(&mut buffer),
+        Err(Dop2CodecError::FrameTooLarge { length: 0x2d0, max: 256 })
+    ));
+
+    let mut buffer = BytesMut::from(&bytes[..100]);
+    assert!(Dop2Codec::new().decode(&mut buffer).unwrap().is_none());
+    assert!(matches!(
+        Dop2Codec::new().decode_eof(&mut buffer),
+        Err(Dop2CodecError::TruncatedFrame { available: 100 })
+    ));
+}
+
+#[test]
+fn test_json_shape_of_parse_tree() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    let json = serde_json::to_value(&root_node).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "unit": 2,
+            "attribute": 1586,
+            "declared_length": 22,
+            "idx1": 0,
+            "idx2": 0,
+            "root_struct": { "1": { "E8": 4 }, "2": { "E8": 5 }, "3": { "E8": 1 } }
+        })
+    );
+}
+
+#[test]
+fn test_hand_written_json_becomes_wire_payload() {
+    let json = r#"{
+        "unit": 2,
+        "attribute": 1585,
+        "root_struct": {
+            "1": { "E16": 13 },
+            "2": { "MStruct": { "1": { "Boolean": true }, "2": { "ArrayU16": [1, 2] } } },
+            "3": { "MString": "abc" }
+        }
+    }"#;
+    let root_node: RootNode = serde_json::from_str(json).unwrap();
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+
+    let parsed = RootNode::parse(&mut Dop2Parser::new(&data)).unwrap();
+    assert_eq!(parsed.root_struct.get_payload(1), Some(Dop2Payloads::E16(E16(13))));
+    assert_eq!(parsed.root_struct.get_payload(3), Some(Dop2Payloads::MString("abc".to_string())));
+    assert_eq!(parsed.declared_length, 41);
+    assert_eq!(data.len(), 48);
+    assert_eq!(serde_json::to_value(&parsed.root_struct).unwrap(), serde_json::from_str::<serde_json::Value>(json).unwrap()["root_struct"]);
+}
+
+#[test]
+fn test_corpus_round_trips_through_json() {
+    for (name, frame) in corpus() {
+        let bytes = hex::decode(&frame).unwrap();
+        let Ok(root_node) = RootNode::parse(&mut Dop2Parser::new(&bytes)) else {
+            continue;
+        };
+        let json = serde_json::to_string(&root_node).unwrap();
+        let decoded: RootNode = serde_json::from_str(&json).unwrap();
+        let mut data: Vec<u8> = Vec::new();
+        decoded.to_bytes(&mut data).unwrap();
+        assert_eq!(hex::encode(&data), hex::encode(&bytes), "{} did not round trip through {}", name, json);
+    }
+}
+
+#[test]
+fn test_trash_payloads_are_rejected_instead_of_encoded() {
+    let error = serde_json::from_str::<Dop2Struct>(r#"{"1": "Trash"}"#).unwrap_err();
+    assert!(error.to_string().contains("unknown variant `Trash`"), "{}", error);
+
+    let root_node = RootNode::single(2, 1586, Dop2Struct::from_fields(vec![TaggedDopField::from_payload(1, Dop2Payloads::Trash)]).unwrap());
+    let error = root_node.to_bytes(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.path().to_string(), "unit 2 / attr 1586 / field 1");
+}
+
+#[test]
+fn test_notation_encodes_to_fixture() {
+    let root_node: RootNode = "2/1586 { 1: E8(4), 2: E8(5), 3: E8(1) }".parse().unwrap();
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data), TEST_PAYLOADS.oven_2_1586);
+
+    let root_node: RootNode = "14/130:1/1 {
+        1: E8(0),
+    }"
+    .parse()
+    .unwrap();
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(hex::encode(&data), TEST_PAYLOADS.oven_14_130);
+}
+
+#[test]
+fn test_notation_prints_nested_values() {
+    let fields = vec![
+        TaggedDopField::from_payload(1, Dop2Payloads::E16(E16(13))),
+        TaggedDopField::from_payload(2, Dop2Payloads::MStruct(Dop2Struct::from_fields(vec![
+            TaggedDopField::from_payload(1, Dop2Payloads::MString("a \"b\"\u{1}".to_string())),
+            TaggedDopField::from_payload(2, Dop2Payloads::F64(OrderedFloat(-2.0))),
+        ]).unwrap())),
+        TaggedDopField::from_payload(3, Dop2Payloads::ArrayI16(DopArray { count: 2, elements: vec![-1, 7] })),
+        TaggedDopField::from_payload(4, Dop2Payloads::AStruct(DopArray { count: 0, elements: vec![] })),
+    ];
+    let root_node = RootNode::single(2, 1577, Dop2Struct::from_fields(fields).unwrap());
+    let text = root_node.to_string();
+    assert_eq!(
+        text,
+        r#"2/1577 { 1: E16(13), 2: { 1: MString("a \"b\"\x01"), 2: F64(-2.0) }, 3: ArrayI16[-1, 7], 4: AStruct[] }"#
+    );
+    let parsed: RootNode = text.parse().unwrap();
+    assert_eq!(parsed.root_struct, root_node.root_struct);
+}
+
+#[test]
+fn test_notation_reports_error_position() {
+    let error = "2/1577 { 1: E16(13), 2: Q16(0) }".parse::<RootNode>().unwrap_err();
+    assert_eq!(error.position, 24);
+    assert_eq!(error.to_string(), "unknown payload kind 'Q16' at position 24");
+
+    let error = "2/1577 { 1: U8(300) }".parse::<RootNode>().unwrap_err();
+    assert_eq!(error.position, 15);
+}
+
+#[test]
+fn test_corpus_round_trips_through_notation() {
+    for (name, frame) in corpus() {
+        let bytes = hex::decode(&frame).unwrap();
+        let Ok(root_node) = RootNode::parse(&mut Dop2Parser::new(&bytes)) else {
+            continue;
+        };
+        for text in [format!("{}", root_node), format!("{:#}", root_node)] {
+            let parsed: RootNode = text.parse().unwrap();
+            let mut data: Vec<u8> = Vec::new();
+            parsed.to_bytes(&mut data).unwrap();
+            assert_eq!(hex::encode(&data), hex::encode(&bytes), "{} did not round trip through {}", name, text);
+        }
+    }
+}
+
+#[test]
+fn test_annotated_dump_names_registered_fields() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap();
+    let dump = annotated_dump(&bytes, &AttributeRegistry::new());
+    let expected = "\
+0000  00 16                    declared_length 22
+0002  00 02                    unit 2
+0004  06 32                    attribute 1586 (DeviceCombiState)
+0006  00 00                    idx1 0
+0008  00 00                    idx2 0
+000a  00 03                    struct, 3 fields {
+000c  00 01 04 04                field 1 appliance_state: E8(4)
+0010  00 02 04 05                field 2 operation_state: E8(5)
+0014  00 03 04 01                field 3 process_state: E8(1)
+0018                           }
+0018  20 20 20 20 20 20 20 20  padding, 8 bytes
+";
+    assert_eq!(dump, expected);
+}
+
+#[test]
+fn test_annotated_dump_shows_nesting_and_errors() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    let dump = annotated_dump(&bytes, &AttributeRegistry::new());
+    assert!(dump.contains("000c  00 01 21                   field 1: AStruct\n000f  00 02                      2 elements\n"));
+    assert!(dump.contains("0011                             element 0\n"));
+    assert!(dump.contains("003e  00 02 09 00 00 01 5c         field 2: I32(348)\n"));
+
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_invalid_boolean).unwrap();
+    let dump = annotated_dump(&bytes, &AttributeRegistry::new());
+    assert!(dump.ends_with("000c                           error: field 1: invalid Boolean value 0xFF at offset 15\n000c  00 01 01 ff              undecoded\n"));
+}
+
+#[test]
+fn test_path_get_and_set_inside_device_context() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_391).unwrap();
+    let mut root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    assert_eq!(root_node.get("7.21.2"), Some(&Dop2Payloads::U16(0)));
+    assert_eq!(root_node.get("7.21.9"), None);
+    assert_eq!(root_node.get("7.21.2.1"), None);
+    assert_eq!(root_node.get("7..2"), None);
+
+    let previous = root_node.set("7.21.2", Dop2Payloads::U16(180)).unwrap();
+    assert_eq!(previous, Some(Dop2Payloads::U16(0)));
+    assert!(matches!(root_node.set("7.99.1", Dop2Payloads::U8(1)), Err(Dop2Error::PathNotFound { .. })));
+    assert!(matches!(root_node.set("7[x]", Dop2Payloads::U8(1)), Err(Dop2Error::InvalidPath { .. })));
+
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    let changed: Vec<usize> = (0..bytes.len()).filter(|i| bytes[*i] != data[*i]).collect();
+    assert_eq!(data.len(), bytes.len());
+    assert_eq!(changed.len(), 1);
+    assert_eq!(data[changed[0]], 180);
+    let reparsed = RootNode::parse(&mut Dop2Parser::new(&data)).unwrap();
+    assert_eq!(reparsed.get("7.21.2"), Some(&Dop2Payloads::U16(180)));
+}
+
+#[test]
+fn test_path_into_struct_arrays_and_new_fields() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    let mut root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    assert_eq!(root_node.get("2[0].2"), Some(&Dop2Payloads::I32(348)));
+    assert_eq!(root_node.get("2[2].2"), None);
+
+    assert_eq!(root_node.set("2[1].3", Dop2Payloads::MString("new".to_string())).unwrap(), None);
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    let reparsed = RootNode::parse(&mut Dop2Parser::new(&data)).unwrap();
+    assert_eq!(reparsed.get("2[1].3"), Some(&Dop2Payloads::MString("new".to_string())));
+}
+
+#[test]
+fn test_path_leaves_match_get() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    let leaves = root_node.root_struct.leaves();
+    let paths: Vec<String> = leaves.iter().map(|(path, _)| path.dotted()).collect();
+    assert_eq!(paths, vec!["1[0].1", "1[0].2", "1[1].1", "1[1].2", "2[0].1", "2[0].2", "2[1].1", "2[1].2"]);
+    for (path, value) in leaves {
+        assert_eq!(root_node.get(&path.dotted()), Some(value));
+        assert_eq!(root_node.root_struct.get_path(&path), Some(value));
+    }
+}
+
+#[test]
+fn test_error_paths_resolve_in_the_tree() {
+    // the path of a conversion error leads to the payload that failed to convert
+    let bytes = hex::decode(TEST_PAYLOADS.synthetic_garbage_missing_field).unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    let error = CSContext::try_from(root_node.root_struct.clone()).unwrap_err();
+    assert_eq!(root_node.root_struct.get_path(error.path()), Some(&Dop2Payloads::E8(E8(4))));
+
+    // as does the path of a parse error, once the bytes are read leniently
+    let mut bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    bytes[22] = 0x02;
+    let error = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap_err();
+    let lenient = RootNode::parse(&mut Dop2Parser::lenient(&bytes)).unwrap();
+    assert!(matches!(lenient.root_struct.get_path(error.path()), Some(Dop2Payloads::Raw(_))));
+}
+
+/// A complete DeviceState reading with the given remaining time and temperature set points
+fn device_state_reading(remaining_time: u32, temperatures: &str) -> RootNode {
+    format!(
+        "2/256 {{ 1: E8(5), 2: {{ 1: Boolean(true), 2: Boolean(false), 3: Boolean(false), 4: Boolean(true) }}, \
+         3: E8(1), 4: E16(13), 5: E16(3), 6: U32(0), 7: U32({}), 8: U32(60), \
+         9: ArrayI16[{}], 10: ArrayI16[1750, 0], 11: ArrayI16[], 12: ArrayI16[], \
+         13: Boolean(false), 14: Boolean(true), 17: E8(38), 18: E8(0), 19: I32(-1), 20: I32(0), 21: I32(7) }}",
+        remaining_time, temperatures
+    )
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn test_diff_names_typed_fields() {
+    let registry = AttributeRegistry::new();
+    let old = device_state_reading(600, "1800, 0");
+    let new = device_state_reading(540, "1800, 1750");
+    let diff = Dop2Diff::new(&old, &new, &registry).unwrap();
+    assert_eq!(diff.type_name, Some("DeviceState"));
+    assert_eq!(
+        diff.to_string(),
+        "2/256 (DeviceState): 2 changes\n\
+         ~ 7 (DeviceState.remaining_time): U32(600) -> U32(540)\n\
+         ~ 9[1] (DeviceState.process_temperature_set[1]): I16(0) -> I16(1750)\n\
+         = DeviceState.remaining_time: 600 -> 540\n\
+         = DeviceState.process_temperature_set: DopArray { count: 2, elements: [1800, 0] } \
+         -> DopArray { count: 2, elements: [1800, 1750] }\n"
+    );
+    assert!(Dop2Diff::new(&old, &old, &registry).unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_refuses_different_leaves_and_keeps_undecodable_readings() {
+    let registry = AttributeRegistry::new();
+    let state = device_state_reading(600, "1800, 0");
+    let other: RootNode = "1/256 { 1: E8(5) }".parse().unwrap();
+    let error = Dop2Diff::new(&state, &other, &registry).unwrap_err();
+    assert_eq!(error, Dop2DiffError::DifferentLeaves { old: (2, 256), new: (1, 256) });
+    assert_eq!(error.to_string(), "cannot diff 2/256 against 1/256");
+    // nothing is registered at 1/256, so its readings are only diffed as parse trees
+    assert_eq!(Dop2Diff::new(&other, &other, &registry).unwrap().type_name, None);
+
+    // a DeviceState without field 2 does not decode, its parse tree diff is still there
+    let partial: RootNode = "2/256 { 1: E8(5), 7: U32(540) }".parse().unwrap();
+    let diff = Dop2Diff::new(&state, &partial, &registry).unwrap();
+    assert_eq!(diff.changes.len(), 18);
+    assert!(diff.typed_changes.is_empty());
+    let error = diff.typed_error.as_ref().unwrap();
+    assert_eq!(error.path().to_string(), "unit 2 / attr 256 / field 2");
+    let text = diff.to_string();
+    assert!(text.contains("~ 7 (DeviceState.remaining_time): U32(600) -> U32(540)\n"));
+    assert!(text.ends_with(&format!("\n! DeviceState: {}\n", error)));
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["typed_error"], serde_json::Value::String(error.to_string()));
+}
+
+#[test]
+fn test_diff_reports_added_removed_and_retagged_fields() {
+    let old: RootNode = "2/9999 { 1: E8(4), 2: U16(7), 3: ArrayU8[1, 2] }".parse().unwrap();
+    let new: RootNode = "2/9999 { 1: U8(4), 3: ArrayU8[1, 2, 3], 4: { 1: Boolean(true) } }".parse().unwrap();
+    let diff = Dop2Diff::new(&old, &new, &AttributeRegistry::new()).unwrap();
+    let summary: Vec<(Dop2ChangeKind, String)> = diff.changes.iter().map(|c| (c.kind, c.path.dotted())).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Dop2ChangeKind::Changed, "1".to_string()),
+            (Dop2ChangeKind::Removed, "2".to_string()),
+            (Dop2ChangeKind::Added, "3[2]".to_string()),
+            (Dop2ChangeKind::Added, "4".to_string()),
+        ]
+    );
+    assert_eq!(diff.changes[0].to_string(), "~ 1: E8(4) -> U8(4)");
+    assert_eq!(diff.changes[1].to_string(), "- 2: U16(7)");
+    assert_eq!(diff.changes[2].field, None);
+}
+
+#[test]
+fn test_diff_descends_into_struct_arrays() {
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    let old = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    let mut new = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    new.set("2[0].2", Dop2Payloads::I32(360)).unwrap();
+    let diff = Dop2Diff::new(&old, &new, &AttributeRegistry::new()).unwrap();
+    assert_eq!(diff.changes.len(), 1);
+    assert_eq!(diff.changes[0].path.dotted(), "2[0].2");
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["unit"], 1);
+    assert_eq!(json["attribute"], 209);
+    assert_eq!(
+        json["changes"][0],
+        serde_json::json!({ "kind": "changed", "path": "2[0].2", "old": { "I32": 348 }, "new": { "I32": 360 } })
+    );
+}
+
+/// Wraps a root struct into a frame of 2/1577 with a correct length word and padding
+fn frame_around(root_struct: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend(((8 + root_struct.len()) as u16).to_be_bytes());
+    frame.extend([0x00, 0x02, 0x06, 0x29, 0x00, 0x00, 0x00, 0x00]);
+    frame.extend(root_struct);
+    let padding = DopPadding::after(frame.len()).bytes_of_padding;
+    frame.extend(vec![0x20; padding.into()]);
+    frame
+}
+
+/// A root struct holding `depth - 1` structs nested inside each other through field 1
+fn nested_structs(depth: usize) -> Vec<u8> {
+    let mut root_struct = Vec::new();
+    for _ in 1..depth {
+        root_struct.extend([0x00, 0x01, 0x00, 0x01, 0x10]);
+    }
+    root_struct.extend([0x00, 0x00]);
+    root_struct
+}
+
+#[test]
+fn test_limit_on_nesting_depth() {
+    let limits = Dop2Limits { max_depth: 4, ..Dop2Limits::default() };
+    let bytes = frame_around(&nested_structs(4));
+    assert!(RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).is_ok());
+
+    let bytes = frame_around(&nested_structs(5));
+    let error = RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LimitExceeded { limit: Dop2Limit::Depth, value: 5, max: 4, .. }));
+    assert_eq!(error.path().dotted(), "1.1.1.1");
+    let mut lenient = Dop2Parser::lenient(&bytes);
+    lenient.set_limits(limits);
+    assert_eq!(RootNode::parse(&mut lenient).unwrap_err(), error);
+
+    // far deeper than the stack would allow without a limit
+    let bytes = frame_around(&nested_structs(10_000));
+    let error = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LimitExceeded { limit: Dop2Limit::Depth, .. }));
+    assert!(annotated_dump(&bytes, &AttributeRegistry::new()).contains("error: "));
+
+    // the text notation nests no deeper than frames do
+    let nested = |depth: usize| format!("1/2 {}{}", "{ 1: ".repeat(depth - 1) + "{", " }".repeat(depth));
+    assert!(nested(32).parse::<RootNode>().is_ok());
+    let error = nested(33).parse::<RootNode>().unwrap_err();
+    assert_eq!(error.message, "structs nested deeper than 32");
+    assert!(nested(10_000).parse::<RootNode>().is_err());
+}
+
+#[test]
+fn test_limit_on_total_elements() {
+    let limits = Dop2Limits { max_elements: 100, ..Dop2Limits::default() };
+    // an ArrayU8 that claims 65535 elements but holds two
+    let bytes = frame_around(&[0x00, 0x01, 0x00, 0x01, 0x12, 0xff, 0xff, 0x01, 0x02]);
+    let error = RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LimitExceeded { limit: Dop2Limit::Elements, offset: 15, value: 65536, .. }));
+    assert_eq!(error.to_string(), "unit 2 / attr 1577 / field 1: element count 65536 at offset 15 exceeds the limit of 100");
+
+    // the budget covers the whole frame, not each array on its own
+    let mut root_struct = vec![0x00, 0x02];
+    for field in 1..=2u8 {
+        root_struct.extend([0x00, field, 0x12, 0x00, 60]);
+        root_struct.extend([0u8; 60]);
+    }
+    let bytes = frame_around(&root_struct);
+    assert!(RootNode::parse(&mut Dop2Parser::new(&bytes)).is_ok());
+    let error = RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).unwrap_err();
+    assert_eq!(error.path().dotted(), "2");
+}
+
+#[test]
+fn test_limit_on_string_length() {
+    let limits = Dop2Limits { max_string_length: 8, ..Dop2Limits::default() };
+    let root_node: RootNode = "2/1577 { 1: MString(\"shortish\"), 2: MString(\"a little longer\") }".parse().unwrap();
+    let mut bytes: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut bytes).unwrap();
+    let error = RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LimitExceeded { limit: Dop2Limit::StringLength, value: 15, max: 8, .. }));
+    assert_eq!(error.path().dotted(), "2");
+}
+
+#[test]
+fn test_limit_on_frame_size() {
+    let limits = Dop2Limits { max_frame_size: 64, ..Dop2Limits::default() };
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_209).unwrap();
+    let error = RootNode::parse(&mut Dop2Parser::with_limits(&bytes, limits)).unwrap_err();
+    assert!(matches!(error, Dop2Error::LimitExceeded { limit: Dop2Limit::FrameSize, offset: 0, value: 96, max: 64, .. }));
+
+    let mut codec = Dop2Codec::with_limits(limits);
+    assert!(matches!(codec.decode_body(&bytes), Err(Dop2CodecError::FrameTooLarge { length: 96, max: 64 })));
+    let limits = Dop2Limits { max_depth: 1, ..Dop2Limits::default() };
+    let mut codec = Dop2Codec::with_limits(limits);
+    assert!(matches!(
+        codec.decode_body(&bytes),
+        Err(Dop2CodecError::Frame(Dop2Error::LimitExceeded { limit: Dop2Limit::Depth, .. }))
+    ));
+}
+
+#[test]
+fn test_round_trip_keeps_missing_and_excess_padding() {
+    // found by the parse_root_node fuzz target: a damaged frame cut off before its padding
+    let bytes = hex::decode("000000000a0000000affffff").unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::lenient(&bytes)).unwrap();
+    let mut data: Vec<u8> = Vec::new();
+    root_node.to_bytes(&mut data).unwrap();
+    assert_eq!(data, bytes);
+
+    let mut bytes = hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap();
+    for length in [bytes.len() - 8, bytes.len() + 300] {
+        bytes.resize(length, 0x20);
+        let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+        let mut data: Vec<u8> = Vec::new();
+        root_node.to_bytes(&mut data).unwrap();
+        assert_eq!(data, bytes);
+    }
+}
+
+/// Writes the corpus into the seed directories of the fuzz targets in `fuzz/`;
+/// run with `cargo test write_fuzz_seed_corpus -- --ignored`
+#[test]
+#[ignore]
+fn write_fuzz_seed_corpus() {
+    for target in ["parse_root_node", "from_parse_tree"] {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus").join(target);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, frame) in corpus() {
+            let file_name: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+            std::fs::write(dir.join(file_name), hex::decode(frame).unwrap()).unwrap();
+        }
+    }
+}
+
+fn array_of<T: Dop2PayloadExpressible + ToDop2Bytes>(elements: Vec<T>) -> DopArray<T> {
+    DopArray { count: elements.len() as u16, elements }
+}
+
+/// Every payload kind that has a wire tag, `MString`s limited to the bytes the parser maps to chars
+fn scalar_payload() -> impl Strategy<Value = Dop2Payloads> {
+    fn elements<T: Arbitrary>() -> impl Strategy<Value = Vec<T>> {
+        prop::collection::vec(any::<T>(), 0..8)
+    }
+    prop_oneof![
+        any::<bool>().prop_map(Dop2Payloads::Boolean),
+        any::<u8>().prop_map(Dop2Payloads::U8),
+        any::<i8>().prop_map(Dop2Payloads::I8),
+        any::<u8>().prop_map(|v| Dop2Payloads::E8(E8(v))),
+        any::<u16>().prop_map(Dop2Payloads::U16),
+        any::<i16>().prop_map(Dop2Payloads::I16),
+        any::<u16>().prop_map(|v| Dop2Payloads::E16(E16(v))),
+        any::<u32>().prop_map(Dop2Payloads::U32),
+        any::<i32>().prop_map(Dop2Payloads::I32),
+        any::<u32>().prop_map(|v| Dop2Payloads::E32(E32(v))),
+        any::<u64>().prop_map(Dop2Payloads::U64),
+        any::<i64>().prop_map(Dop2Payloads::I64),
+        any::<u64>().prop_map(|v| Dop2Payloads::E64(E64(v))),
+        any::<f32>().prop_map(|v| Dop2Payloads::F32(OrderedFloat(v))),
+        any::<f64>().prop_map(|v| Dop2Payloads::F64(OrderedFloat(v))),
+        prop::collection::vec(any::<u8>(), 0..40).prop_map(|bytes| Dop2Payloads::MString(bytes.into_iter().map(char::from).collect())),
+        elements::<bool>().prop_map(|e| Dop2Payloads::ArrayBool(array_of(e))),
+        elements::<u8>().prop_map(|e| Dop2Payloads::ArrayU8(array_of(e))),
+        elements::<i8>().prop_map(|e| Dop2Payloads::ArrayI8(array_of(e))),
+        elements::<u8>().prop_map(|e| Dop2Payloads::ArrayE8(array_of(e.into_iter().map(E8).collect()))),
+        elements::<u16>().prop_map(|e| Dop2Payloads::ArrayU16(array_of(e))),
+        elements::<i16>().prop_map(|e| Dop2Payloads::ArrayI16(array_of(e))),
+        elements::<u16>().prop_map(|e| Dop2Payloads::ArrayE16(array_of(e.into_iter().map(E16).collect()))),
+        elements::<u32>().prop_map(|e| Dop2Payloads::ArrayU32(array_of(e))),
+        elements::<i32>().prop_map(|e| Dop2Payloads::ArrayI32(array_of(e))),
+        elements::<u32>().prop_map(|e| Dop2Payloads::ArrayE32(array_of(e))),
+        elements::<u64>().prop_map(|e| Dop2Payloads::ArrayU64(array_of(e))),
+        elements::<i64>().prop_map(|e| Dop2Payloads::ArrayI64(array_of(e))),
+        elements::<u64>().prop_map(|e| Dop2Payloads::ArrayE64(array_of(e))),
+        elements::<f32>().prop_map(|e| Dop2Payloads::ArrayF32(array_of(e.into_iter().map(OrderedFloat).collect()))),
+        elements::<f64>().prop_map(|e| Dop2Payloads::ArrayF64(array_of(e.into_iter().map(OrderedFloat).collect()))),
+    ]
+}
+
+fn struct_of(payload: impl Strategy<Value = Dop2Payloads>) -> impl Strategy<Value = Dop2Struct> {
+    prop::collection::vec((any::<u16>(), payload), 0..6).prop_map(|fields| {
+        Dop2Struct::from_fields(fields.into_iter().map(|(index, value)| TaggedDopField::from_payload(index, value)).collect()).unwrap()
+    })
+}
+
+/// Trees of up to four nested `MStruct`s and `AStruct`s
+fn payload_tree() -> impl Strategy<Value = Dop2Payloads> {
+    scalar_payload().prop_recursive(4, 64, 6, |inner| {
+        let nested = struct_of(inner).boxed();
+        prop_oneof![
+            nested.clone().prop_map(Dop2Payloads::MStruct),
+            prop::collection::vec(nested, 0..4).prop_map(|e| Dop2Payloads::AStruct(array_of(e))),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn prop_parse_inverts_to_bytes(unit: u16, attribute: u16, root_struct in struct_of(payload_tree())) {
+        let mut bytes: Vec<u8> = Vec::new();
+        RootNode::single(unit, attribute, root_struct.clone()).to_bytes(&mut bytes).unwrap();
+        let mut parser = Dop2Parser::new(&bytes);
+        let parsed = RootNode::parse(&mut parser).unwrap();
+        prop_assert!(parser.is_empty());
+        prop_assert_eq!((parsed.unit, parsed.attribute), (unit, attribute));
+        prop_assert_eq!(&parsed.root_struct, &root_struct);
+
+        let mut again: Vec<u8> = Vec::new();
+        parsed.to_bytes(&mut again).unwrap();
+        prop_assert_eq!(again, bytes);
+    }
+
+    #[test]
+    fn prop_damaged_frames_round_trip_or_fail_cleanly(
+        frame in prop::sample::select(corpus()),
+        damage in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..4),
+    ) {
+        let mut bytes = hex::decode(&frame.1).unwrap();
+        for (index, byte) in damage {
+            let index = index.index(bytes.len());
+            bytes[index] = byte;
+        }
+        let mut parser = Dop2Parser::new(&bytes);
+        if let Ok(root_node) = RootNode::parse(&mut parser) {
+            let consumed = parser.position();
+            let mut data: Vec<u8> = Vec::new();
+            root_node.to_bytes(&mut data).unwrap();
+            prop_assert_eq!(&data[..], &bytes[..consumed]);
+        }
+        let mut parser = Dop2Parser::lenient(&bytes);
+        if let Ok(root_node) = RootNode::parse(&mut parser) {
+            let consumed = parser.position();
+            let mut data: Vec<u8> = Vec::new();
+            root_node.to_bytes(&mut data).unwrap();
+            prop_assert_eq!(&data[..], &bytes[..consumed]);
+        }
+    }
+}
+
 #[test]
 fn test_device_combo_state() {
     // Test that we can use it with our parser
-    let mut parser = Dop2Parser::new(hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap());
+    let bytes = hex::decode(TEST_PAYLOADS.oven_2_1586).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     assert!(result.is_ok());
     let _root_node = result.unwrap();
@@ -86,7 +923,8 @@ fn test_device_combo_state() {
 
 #[test]
 fn test_washer_context_optionals() {
-    let mut parser = Dop2Parser::new(hex::decode(TEST_PAYLOADS.washer_1_154).unwrap());
+    let bytes = hex::decode(TEST_PAYLOADS.washer_1_154).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     assert!(result.is_ok());
     let root_node = result.unwrap();
@@ -146,6 +984,8 @@ fn test_washer_context_optionals() {
             },
         }),
         context_oven: None,
+        #[cfg(feature = "coffee")]
+        context_coffee_maker: None,
     };
     
     // Test that the parsed CSContext matches the expected structure
@@ -153,34 +993,435 @@ fn test_washer_context_optionals() {
     
     // Round-trip test: serialize back to bytes and verify it matches original
     let mut data: Vec<u8> = Vec::new();
-    let _bytes = root_node.to_bytes(&mut data);
+    root_node.to_bytes(&mut data).unwrap();
     let hex_string = hex::encode(&data);
     assert_eq!(hex_string, TEST_PAYLOADS.washer_1_154);
 }
 
 fn test_round_trip (payload: &str)
 {
-    let mut parser = Dop2Parser::new(hex::decode(payload).unwrap());
+    let bytes = hex::decode(payload).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     assert!(result.is_ok());
     let root_node = result.unwrap();
     let mut data :  Vec<u8> = Vec::new();
-    let _bytes = root_node.to_bytes(&mut data);
+    root_node.to_bytes(&mut data).unwrap();
     let hex_string = hex::encode(&data);
     assert_eq!(hex_string, payload);
 }
 #[test]
 fn test_static_payloads() {
     // Test that we can use it with our parser
-    let mut parser = Dop2Parser::new(hex::decode(TEST_PAYLOADS.oven_14_130).unwrap());
+    let bytes = hex::decode(TEST_PAYLOADS.oven_14_130).unwrap();
+    let mut parser = Dop2Parser::new(&bytes);
     let result = RootNode::parse(&mut parser);
     assert!(result.is_ok());
 
     let root_node = result.unwrap();
     let mut data :  Vec<u8> = Vec::new();
     //root_node.padding=None;
-    let _bytes = root_node.to_bytes(&mut data);
+    root_node.to_bytes(&mut data).unwrap();
     let hex_string = hex::encode(&data);
     assert_eq!(hex_string, TEST_PAYLOADS.oven_14_130);
 }
 
+
+#[test]
+fn test_getters_and_constructor_of_private_fields() {
+    use crate::payloader::meta::object_ids::SysObjectId;
+    let bytes = hex::decode(TEST_PAYLOADS.oven_9_19).unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    let decoded = SysObjectId::from_parse_tree(Dop2Payloads::MStruct(root_node.root_struct.clone())).unwrap();
+    assert_eq!(*decoded.object_id(), 0xab);
+    assert_eq!(*decoded.instances(), 1);
+    assert_eq!(*decoded.auth_read(), 0xfb00);
+
+    let built = SysObjectId::new(0xab, 1, 0xfb00, 0xfb00, 0);
+    assert_eq!(built, decoded);
+    assert_eq!(built.to_dop2_struct_auto().unwrap(), root_node.root_struct);
+}
+
+#[test]
+fn test_conversion_error_names_struct_field_and_raw_value() {
+    use crate::payloader::device::generic::state::cs_context::PartName;
+    let root_node: RootNode = "1/173 { 1: ArrayU8[255], 2: ArrayU8[65] }".parse().unwrap();
+    let error = PartName::from_parse_tree(Dop2Payloads::MStruct(root_node.root_struct)).unwrap_err();
+    assert_eq!(error.type_name(), Some("PartName"));
+    assert_eq!(error.path().segments(), &[Dop2PathSegment::Field(1)]);
+    assert_eq!(error.to_string(), "field 1: cannot convert invalid ASCII/UTF-8 ff into String while building PartName");
+}
+
+mod optional_names {
+    use crate::payloader::prelude::*;
+    use crate::payloader::root::Dop2ParseTreeExpressible;
+
+    #[derive(Debug, Clone, PartialEq, Eq, AssocTypes)]
+    pub struct OptionalNames {
+        #[dop2field(1, Dop2Payloads::ArrayU8)]
+        strict: Option<String>,
+        #[dop2field(2, Dop2Payloads::ArrayU8, lenient)]
+        lenient: Option<String>,
+    }
+}
+
+#[test]
+fn test_option_fields_fail_or_drop_bad_values() {
+    use optional_names::OptionalNames;
+    let parse = |text: &str| {
+        let root_node: RootNode = text.parse().unwrap();
+        OptionalNames::from_parse_tree(Dop2Payloads::MStruct(root_node.root_struct))
+    };
+    let name = Some("A".to_string());
+    assert_eq!(parse("2/1 { 1: ArrayU8[65], 2: ArrayU8[65] }").unwrap(), OptionalNames::new(name.clone(), name));
+    assert_eq!(parse("2/1 { }").unwrap(), OptionalNames::new(None, None));
+
+    assert_eq!(*parse("2/1 { 2: ArrayU8[255] }").unwrap().lenient(), None);
+    assert_eq!(*parse("2/1 { 2: U8(1) }").unwrap().lenient(), None);
+
+    // a payload of another kind is not the field, a value of the right kind has to convert
+    assert_eq!(*parse("2/1 { 1: U8(1) }").unwrap().strict(), None);
+    let error = parse("2/1 { 1: ArrayU8[255] }").unwrap_err();
+    assert!(matches!(error, Dop2Error::InvalidConversion { type_name: "OptionalNames", .. }));
+    assert_eq!(error.path().segments(), &[Dop2PathSegment::Field(1)]);
+}
+
+/// Decodes the frame into `T`, encodes it again and expects the same struct back
+fn assert_typed_round_trip<T>(text: &str) -> T
+where
+    T: Dop2ParseTreeExpressible + TryInto<Dop2Struct, Error = Dop2Error> + Clone + std::fmt::Debug,
+{
+    let root_node: RootNode = text.parse().unwrap();
+    let decoded = T::from_parse_tree(Dop2Payloads::MStruct(root_node.root_struct.clone())).unwrap();
+    let encoded: Dop2Struct = decoded.clone().try_into().unwrap();
+    assert_eq!(encoded, root_node.root_struct, "{}", text);
+    decoded
+}
+
+#[cfg(feature = "coffee")]
+#[test]
+fn test_coffee_maker_context_round_trips() {
+    let generic = "{ 1: U8(0), 2: U8(0), 3: U8(2), 4: U8(1), 5: U8(1) }";
+    let context: CSContext = assert_typed_round_trip(&format!(
+        "2/154 {{ 1: E16(0), 4: {{ 5: {g}, 6: {g}, 7: {g}, 8: {g}, 12: {g} }} }}",
+        g = generic
+    ));
+    assert_eq!(context.context_coffee_maker.map(|coffee| coffee.pump.current), Some(1));
+}
+
+#[test]
+fn test_option_fields_round_trip_user_request() {
+    use crate::payloader::device::generic::request::UserRequest;
+    let request: UserRequest = assert_typed_round_trip("2/1583 { 1: E16(2), 2: U16(60), 3: U16(1) }");
+    assert_eq!((request.parameter0, request.parameter1), (Some(60), Some(1)));
+    let request: UserRequest = assert_typed_round_trip("2/1583 { 1: E16(2), 2: U16(60) }");
+    assert_eq!((request.parameter0, request.parameter1), (Some(60), None));
+    let request: UserRequest = assert_typed_round_trip("2/1583 { 1: E16(2) }");
+    assert_eq!((request.parameter0, request.parameter1), (None, None));
+
+    let request = UserRequest { parameter1: Some(3), ..request };
+    let encoded: Dop2Struct = request.try_into().unwrap();
+    assert_eq!(encoded.fields.iter().map(|f| f.field_index).collect::<Vec<_>>(), vec![1, 3]);
+}
+
+#[test]
+fn test_option_fields_round_trip_update_container_information() {
+    use crate::payloader::comm_module::update::container_info::UpdateContainerInformation;
+    let info: UpdateContainerInformation = assert_typed_round_trip(
+        "14/397 { 1: U8(2), 2: ArrayU8[69, 76, 49], 3: U32(4096), 7: U32(12), 11: U32(3735928559) }",
+    );
+    assert_eq!(info.field2().as_deref(), Some("EL1"));
+    assert_eq!((*info.field3(), *info.field4()), (Some(4096), None));
+    assert_typed_round_trip::<UpdateContainerInformation>("14/397 { 1: U8(0), 11: U32(0) }");
+}
+
+#[test]
+fn test_option_fields_round_trip_device_state() {
+    use crate::payloader::device::generic::state::device_state::DeviceState;
+    let fixed = "1: E8(5), 2: { 1: Boolean(true), 2: Boolean(false), 3: Boolean(false), 4: Boolean(true) }, \
+                 3: E8(1), 4: E16(13), 5: E16(3), 6: U32(0), 7: U32(540), 8: U32(60), \
+                 9: ArrayI16[1800, 0], 10: ArrayI16[1750, 0], 11: ArrayI16[], 12: ArrayI16[], \
+                 13: Boolean(false), 14: Boolean(true)";
+    let tail = "17: E8(38), 18: E8(0), 19: I32(-1), 20: I32(0), 21: I32(7)";
+    let state: DeviceState = assert_typed_round_trip(&format!("1/256 {{ {}, 15: U16(1400), 16: E8(2), {} }}", fixed, tail));
+    assert_eq!((*state.spinning_speed(), *state.drying_step()), (Some(1400), Some(E8(2))));
+    let state: DeviceState = assert_typed_round_trip(&format!("1/256 {{ {}, {} }}", fixed, tail));
+    assert_eq!((*state.spinning_speed(), *state.drying_step()), (None, None));
+}
+
+#[test]
+fn test_unknown_enum_values_survive_a_round_trip() {
+    use crate::payloader::device::generic::program_selection::select::PsSelect;
+    let select: PsSelect = assert_typed_round_trip("2/1577 { 1: E16(9999), 2: U16(0), 3: E8(1) }");
+    assert_eq!(select.program_id, ProgramIdOven::Unknown(9999));
+    assert!(select.program_id.is_unknown());
+    assert_eq!(select.program_id.to_string(), "Unknown(9999)");
+    assert_eq!(ProgramIdOven::from(E16(2)), ProgramIdOven::AutoHotAir);
+    assert_eq!(E16::from(ProgramIdOven::AutoHotAir), E16(2));
+    assert!("Unknown".parse::<ProgramIdOven>().is_err());
+
+    let states: Vec<ProcessState> = DopArray { count: 2, elements: vec![E8(4), E8(200)] }.try_into().unwrap();
+    assert_eq!(states, vec![ProcessState::ProgramRunning, ProcessState::Other(200)]);
+    let array: DopArray<E8> = states.try_into().unwrap();
+    assert_eq!(array.elements, vec![E8(4), E8(200)]);
+
+    let error = E8::try_from(ProcessState::Other(300)).unwrap_err();
+    assert_eq!(error.to_string(), "cannot convert ProcessState::Other(300) while building E8");
+}
+
+#[test]
+fn test_declared_unit_and_attribute_register_and_build_frames() {
+    use crate::attribute_registry::AttributeRegistration;
+    use crate::payloader::attribute_ids::HasAttributeIds;
+    use crate::payloader::comm_module::request::request::{XkmRequest, XkmRequestId};
+    use crate::payloader::device::generic::notifications::DeviceNotifications;
+
+    // no leaf is claimed by two types
+    let mut leaves: Vec<(u16, u16)> = inventory::iter::<AttributeRegistration>
+        .into_iter()
+        .flat_map(|registration| registration.leaves.iter().copied())
+        .collect();
+    let count = leaves.len();
+    leaves.sort_unstable();
+    leaves.dedup();
+    assert_eq!(leaves.len(), count);
+
+    let registry = AttributeRegistry::new();
+    assert_eq!(registry.leaves(), leaves);
+    assert_eq!(registry.schema(14, 130).map(|(name, _)| name), Some("XkmRequest"));
+    assert_eq!(registry.schema(2, 131).map(|(name, _)| name), Some("DeviceNotifications"));
+    assert_eq!(registry.schema(2, 392).map(|(name, _)| name), Some("DeviceNotifications"));
+    assert!(registry.schema(1, 130).is_none());
+    assert_eq!(DeviceNotifications::LEAVES, &[(2, 131), (2, 392)]);
+
+    let root_node = XkmRequest { request_id: XkmRequestId::Reset }.to_root_node().unwrap();
+    assert_eq!((root_node.unit, root_node.attribute), (14, 130));
+    assert_eq!(root_node.to_string(), "14/130 { 1: E8(1) }");
+}
+
+#[test]
+fn test_one_type_at_leaves_of_different_units() {
+    use crate::payloader::attribute_ids::HasAttributeIds;
+    use crate::payloader::device::generic::context::DeviceContext;
+
+    assert_eq!(DeviceContext::LEAVES, &[(1, 391), (2, 1585)]);
+    let registry = AttributeRegistry::new();
+    assert_eq!(registry.schema(1, 391).map(|(name, _)| name), Some("DeviceContext"));
+    assert_eq!(registry.schema(2, 1585).map(|(name, _)| name), Some("DeviceContext"));
+    assert!(registry.schema(2, 391).is_none());
+
+    let bytes = hex::decode(TEST_PAYLOADS.oven_1_391).unwrap();
+    let root_node = RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap();
+    assert!(registry.decode(root_node.unit, root_node.attribute, root_node.root_struct.clone()).unwrap().is_ok());
+    let context = DeviceContext::from_parse_tree(Dop2Payloads::MStruct(root_node.root_struct)).unwrap();
+    let rebuilt = context.to_root_node().unwrap();
+    assert_eq!((rebuilt.unit, rebuilt.attribute), (1, 391));
+}
+
+#[test]
+fn test_derive_rejects_invalid_field_declarations() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+    cases.pass("tests/ui/pass/*.rs");
+}
+
+#[cfg(feature = "oven")]
+#[test]
+fn test_extra_fields_survive_read_modify_write() {
+    use crate::payloader::device::oven::program_info::ProgramInfoOven;
+    let text = "2/213 { 1: U8(3), 2: U8(1), 3: U16(7), 4: { 1: E8(2) }, 5: Boolean(false), 9: ArrayU8[1, 2] }";
+    let info: ProgramInfoOven = assert_typed_round_trip(text);
+    assert_eq!(info.extra().iter().map(|field| field.field_index).collect::<Vec<_>>(), vec![3, 4, 9]);
+
+    let changed = ProgramInfoOven::new(3, 2, true, info.extra().clone());
+    assert_eq!(
+        changed.to_root_node().unwrap().to_string(),
+        "2/213 { 1: U8(3), 2: U8(2), 3: U16(7), 4: { 1: E8(2) }, 5: Boolean(true), 9: ArrayU8[1, 2] }"
+    );
+
+    // a leaf with only the modelled fields has nothing extra
+    let info: ProgramInfoOven = assert_typed_round_trip("2/213 { 1: U8(3), 2: U8(1), 5: Boolean(false) }");
+    assert!(info.extra().is_empty());
+
+    // fields go back in the order they were read, not by field number
+    assert_typed_round_trip::<ProgramInfoOven>("2/213 { 5: Boolean(false), 1: U8(3), 9: ArrayU8[1], 2: U8(1) }");
+}
+
+#[test]
+fn test_option_values_that_do_not_decode_survive_a_write() {
+    use crate::payloader::device::generic::state::device_state::DeviceState;
+    // the spinning speed of field 15 is a U16, a U8 there stays as extra
+    let text = device_state_reading(600, "1800, 0").to_string().replace("17: E8(38)", "15: U8(3), 17: E8(38)");
+    let state: DeviceState = assert_typed_round_trip(&text);
+    assert_eq!(state.spinning_speed(), &None);
+    assert_eq!(state.extra().iter().map(|field| field.field_index).collect::<Vec<_>>(), vec![15]);
+}
+
+#[test]
+fn test_generic_and_annotated_value_helpers() {
+    use crate::payloader::helper::types::{Annotated, Generic, PhysicalValue};
+
+    let speed = GenericU16 { request_mask: 0, min: 400, max: 1600, current: 1200, step_size: 200 };
+    assert!(speed.is_in_range(400) && speed.is_in_range(1600));
+    assert!(!speed.is_in_range(399) && !speed.is_in_range(1601));
+    assert_eq!(speed.snap_to_step(1290), 1200);
+    assert_eq!(speed.snap_to_step(1310), 1400);
+    assert_eq!(speed.snap_to_step(0), 400);
+    assert_eq!(speed.snap_to_step(2000), 1600);
+    // a maximum off the grid is never passed
+    let offset = Generic::<i16> { request_mask: 0, min: -5, max: 12, current: 0, step_size: 5 };
+    assert_eq!(offset.snap_to_step(12), 10);
+    assert_eq!(offset.snap_to_step(-3), -5);
+    let level = Generic::<f32> { request_mask: 0, min: 0.5, max: 2.0, current: 1.0, step_size: 0.25 };
+    assert_eq!(level.snap_to_step(1.13), 1.25);
+
+    let duration = Annotated { request_mask: 0, value: 90u16, interpretation: ValueInterpretation::DurationMin };
+    assert_eq!(duration.physical_value(), Some(PhysicalValue { value: 5400.0, unit: PhysicalUnit::Second }));
+    let temperature = Annotated { request_mask: 0, value: 18050u16, interpretation: ValueInterpretation::TemperatureC100 };
+    let physical = temperature.physical_value().unwrap();
+    assert!((physical.value - 180.5).abs() < 1e-9 && physical.unit == PhysicalUnit::Celsius);
+    assert_eq!(Annotated { request_mask: 0, value: 3u8, interpretation: ValueInterpretation::Step }.physical_value(), None);
+
+    // any scalar kind, not only the ones with an alias
+    let annotated = Annotated { request_mask: 1, value: -2.5f32, interpretation: ValueInterpretation::TemperatureC1 };
+    let encoded: Dop2Struct = annotated.clone().try_into().unwrap();
+    assert_eq!(RootNode::single(2, 1, encoded.clone()).to_string(), "2/1 { 1: U8(1), 2: F32(-2.5), 3: E8(2) }");
+    assert_eq!(Annotated::<f32>::try_from(encoded.clone()).unwrap(), annotated);
+    assert_eq!(
+        Annotated::<u16>::try_from(encoded).unwrap_err().to_string(),
+        "field 2: expected U16 but found F32 while building Annotated"
+    );
+}
+
+#[test]
+fn test_quantities_follow_interpretation_and_unit_settings() {
+    use crate::payloader::device::generic::settings_value::SfValue;
+    use crate::payloader::helper::types::Annotated;
+    use crate::payloader::units::{Mass, Quantity, Temperature, UnitPreferences};
+    use std::time::Duration;
+
+    let setting: SfValue = assert_typed_round_trip(
+        "2/105 { 1: E16(1006), 2: U8(1), 3: E8(3), 4: I16(18000), 5: I16(3000), 6: I16(25000), 7: I16(18000), \
+         8: U16(0), 9: U8(5), 10: Boolean(false), 11: Boolean(false) }",
+    );
+    assert_eq!(setting.quantity(), Some(Quantity::Temperature(Temperature::from_celsius(180.0))));
+    // quantities are shown in the units the preferences name, never implicitly
+    let metric = UnitPreferences::default();
+    let range = setting.quantity_range().map(|(min, max)| (metric.show(&min).unwrap().to_string(), metric.show(&max).unwrap().to_string()));
+    assert_eq!(range, Some(("30 °C".into(), "250 °C".into())));
+
+    // the device shows Fahrenheit and pounds
+    let units: Vec<SfValue> = [
+        "2/105 { 1: E16(22), 2: U8(1), 3: E8(24), 4: I16(1), 5: I16(0), 6: I16(1), 7: I16(0), 8: U16(0), 9: U8(1), 10: Boolean(false), 11: Boolean(false) }",
+        "2/105 { 1: E16(23), 2: U8(1), 3: E8(25), 4: I16(1), 5: I16(0), 6: I16(1), 7: I16(0), 8: U16(0), 9: U8(1), 10: Boolean(false), 11: Boolean(false) }",
+    ]
+    .iter()
+    .map(|text| assert_typed_round_trip(text))
+    .collect();
+    let preferences = UnitPreferences::from_settings(&units);
+    let shown = preferences.show(&setting.quantity().unwrap()).unwrap();
+    assert_eq!((shown.value.round(), shown.unit), (356.0, PhysicalUnit::Fahrenheit));
+
+    let mut changed = setting.clone();
+    changed.set_quantity(&Quantity::Temperature(preferences.temperature(392.0))).unwrap();
+    assert_eq!(*changed.current_value(), 20000);
+    assert_eq!(
+        changed.set_quantity(&Quantity::Temperature(Temperature::from_celsius(300.0))).unwrap_err().to_string(),
+        "cannot convert 300 °C outside of 3000..=25000 as TemperatureC100 while building SfValue"
+    );
+    assert!(changed.set_quantity(&Quantity::Mass(Mass::from_grams(5.0))).is_err());
+
+    let mut weight = Annotated { request_mask: 0, value: 12345u16, interpretation: ValueInterpretation::WeightTenthOfGram };
+    assert_eq!(weight.quantity(), Some(Quantity::Mass(Mass::from_grams(1234.5))));
+    let shown = preferences.show(&weight.quantity().unwrap()).unwrap();
+    assert_eq!((format!("{:.3}", shown.value), shown.unit), ("2.722".to_string(), PhysicalUnit::Pound));
+    weight.set_quantity(&Quantity::Mass(preferences.mass(1.0))).unwrap();
+    assert_eq!(weight.value, 4536);
+    assert_eq!(
+        weight.set_quantity(&Quantity::Mass(Mass::from_grams(10_000.0))).unwrap_err().to_string(),
+        "cannot convert 10000 g as WeightTenthOfGram into u16 while building Annotated"
+    );
+
+    let remaining = Annotated { request_mask: 0, value: 90u16, interpretation: ValueInterpretation::DurationMin };
+    assert_eq!(remaining.quantity(), Some(Quantity::Duration(Duration::from_secs(5400))));
+}
+
+#[test]
+fn test_scaled_percentages_and_temperatures() {
+    use crate::payloader::helper::types::Annotated;
+    use crate::payloader::units::{Quantity, Temperature};
+
+    let physical = |value: u16, interpretation| Annotated { request_mask: 0, value, interpretation }.physical_value();
+    assert_eq!(physical(7, ValueInterpretation::Percent10), Some(PhysicalValue { value: 70.0, unit: PhysicalUnit::Percent }));
+    assert_eq!(physical(1, ValueInterpretation::Percent100), Some(PhysicalValue { value: 100.0, unit: PhysicalUnit::Percent }));
+    assert_eq!(physical(18, ValueInterpretation::Temperature10C), Some(PhysicalValue { value: 180.0, unit: PhysicalUnit::Celsius }));
+    assert_eq!(physical(35, ValueInterpretation::Temperature10F), Some(PhysicalValue { value: 350.0, unit: PhysicalUnit::Fahrenheit }));
+
+    let mut oven = Annotated { request_mask: 0, value: 18u16, interpretation: ValueInterpretation::Temperature10C };
+    assert_eq!(oven.quantity(), Some(Quantity::Temperature(Temperature::from_celsius(180.0))));
+    oven.set_quantity(&Quantity::Temperature(Temperature::from_celsius(200.0))).unwrap();
+    assert_eq!(oven.value, 20);
+    assert_eq!(oven.physical_value().unwrap().to_string(), "200 °C");
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_timestamps_are_quantities_with_chrono() {
+    use crate::payloader::helper::types::{Annotated, Dop2TimestampUtc};
+    use crate::payloader::units::Quantity;
+
+    let mut clock = Annotated { request_mask: 0, value: 1_700_000_000u32, interpretation: ValueInterpretation::TimeUtc };
+    let time = Dop2TimestampUtc::try_from(1_700_000_000u64).unwrap().0;
+    assert_eq!(clock.quantity(), Some(Quantity::Timestamp(time)));
+    clock.set_quantity(&Quantity::Timestamp(time + chrono::Duration::seconds(60))).unwrap();
+    assert_eq!(clock.value, 1_700_000_060);
+}
+
+#[cfg(feature = "washer")]
+#[test]
+fn test_process_lists_fields_with_quantities() {
+    use crate::payloader::device::washer::process::Process;
+    use crate::payloader::units::{Quantity, Temperature};
+    use std::time::Duration;
+
+    let frame = corpus().into_iter().find(|(name, _)| name == "washer.json 2/6195").unwrap().1;
+    let bytes = hex::decode(frame).unwrap();
+    let mut process = Process::try_from(RootNode::parse(&mut Dop2Parser::new(&bytes)).unwrap().root_struct).unwrap();
+    // the capture was taken between programs, with nothing interpreted
+    assert!(process.quantities().is_empty());
+
+    process.remaining_time_in_minutes.value = 42;
+    process.remaining_time_in_minutes.interpretation = ValueInterpretation::DurationMin;
+    process.fu_temperature.value = 4000;
+    process.fu_temperature.interpretation = ValueInterpretation::TemperatureC100;
+    assert_eq!(
+        process.quantities(),
+        vec![
+            ("remaining_time_in_minutes", Quantity::Duration(Duration::from_secs(2520))),
+            ("fu_temperature", Quantity::Temperature(Temperature::from_celsius(40.0))),
+        ]
+    );
+}
+
+#[cfg(feature = "client")]
+#[test]
+fn test_request_signature_info_from_request() {
+    use crate::crypto::{MieleCryptoError, MieleRequestSignatureInfo};
+    use reqwest::header::HeaderValue;
+    use reqwest::{Method, Request};
+
+    let mut request = Request::new(Method::GET, "http://127.0.0.1:8080/Devices/000177753917/DOP2/2/1585?idx1=0&idx2=1".parse().unwrap());
+    request.headers_mut().insert("Accept", HeaderValue::from_static("application/vnd.miele.v1+json"));
+    let info = MieleRequestSignatureInfo::from_request(&request).unwrap();
+    assert_eq!(info.host, "127.0.0.1:8080");
+    assert_eq!(info.request_uri, "/Devices/000177753917/DOP2/2/1585?idx1=0&idx2=1");
+    assert_eq!((info.accept_header.as_str(), info.date.as_str()), ("application/vnd.miele.v1+json", ""));
+
+    // the default port is left out, like the app does
+    let request = Request::new(Method::GET, "http://127.0.0.1/Devices/".parse().unwrap());
+    assert_eq!(MieleRequestSignatureInfo::from_request(&request).unwrap().host, "127.0.0.1");
+
+    let mut request = Request::new(Method::GET, "http://127.0.0.1/Devices/".parse().unwrap());
+    request.headers_mut().insert("Date", HeaderValue::from_bytes(b"Thu, 01 Jan 1970 \xff").unwrap());
+    assert_eq!(MieleRequestSignatureInfo::from_request(&request).err(), Some(MieleCryptoError::Unsignable("Date")));
+}
@@ -0,0 +1,21 @@
+use dop2rs::payloader::prelude::*;
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct FieldNumber {
+    #[dop2field(one, Dop2Payloads::U16)]
+    pub value: u16,
+}
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct PayloadType {
+    #[dop2field(1, 16)]
+    pub value: u16,
+}
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct UnknownKind {
+    #[dop2field(1, Dop2Payloads::U24)]
+    pub value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dop2rs::payloader::prelude::*;
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct FileTransfer {
+    #[dop2field(7, Dop2Payloads::U32)]
+    pub file_size: u32,
+    #[dop2field(7, Dop2Payloads::ArrayU8)]
+    pub checksum: DopArray<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use dop2rs::payloader::prelude::*;
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct NotExtraFields {
+    #[dop2field(1, Dop2Payloads::U8)]
+    pub step_number: u8,
+    #[dop2(extra)]
+    pub extra: Vec<TaggedDopField>,
+}
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct AlsoAField {
+    #[dop2field(1, Dop2Payloads::U8)]
+    #[dop2(extra)]
+    pub extra: Dop2ExtraFields,
+}
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct TwoExtras {
+    #[dop2(extra)]
+    pub extra: Dop2ExtraFields,
+    #[dop2(extra)]
+    pub more: Dop2ExtraFields,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use dop2rs::payloader::prelude::*;
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct ProgramStepInfoOven {
+    #[dop2field(1, Dop2Payloads::U16)]
+    pub temperature_setpoint: u8,
+}
+
+#[derive(Debug, Clone, AssocTypes)]
+pub struct Temperatures {
+    #[dop2field(1, Dop2Payloads::ArrayU8)]
+    pub values: DopArray<i16>,
+}
+
+fn main() {}
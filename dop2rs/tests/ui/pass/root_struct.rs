@@ -0,0 +1,15 @@
+// A frame root declared outside the crate needs nothing beyond the prelude
+use dop2rs::payloader::prelude::*;
+
+#[derive(Debug, Clone, AssocTypes)]
+#[dop2(leaves = [(1, 391), (2, 1585)])]
+pub struct Context {
+    #[dop2field(1, Dop2Payloads::U8)]
+    pub state: u8,
+}
+
+fn main() {
+    let root_node = Context { state: 3 }.to_root_node().unwrap();
+    assert_eq!((root_node.unit, root_node.attribute), (1, 391));
+    assert_eq!(Context::LEAVES, &[(1, 391), (2, 1585)]);
+}